@@ -0,0 +1,252 @@
+use crate::elp_service::{
+    ask, get_other_voice, prepare_message_for_say, spawn_say, ConfigElp, Generator,
+};
+use crate::error::ElpError;
+use crate::readback::check_readback;
+use rand::Rng;
+use std::path::PathBuf;
+use std::process::Child;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interference {
+    Clean,
+    Overlap,
+    Squeal,
+}
+
+impl Interference {
+    pub fn random<R: Rng>(rng: &mut R) -> Interference {
+        match rng.gen_range(0..=3) {
+            0 => Interference::Clean,
+            1 | 2 => Interference::Overlap,
+            _ => Interference::Squeal,
+        }
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        *self != Interference::Clean
+    }
+}
+
+pub fn is_say_again(answer: &str) -> bool {
+    let answer = answer.to_lowercase().replace(',', " ");
+    let words: Vec<&str> = answer.split_whitespace().collect();
+    words.windows(2).any(|w| w == ["say", "again"])
+}
+
+/// A blocked transmission needs "say again", a clean one a readback of `msg`.
+pub fn check_answer(interference: Interference, msg: &str, answer: &str) -> bool {
+    if interference.is_blocked() {
+        is_say_again(answer)
+    } else {
+        !is_say_again(answer) && check_readback(msg, answer).is_empty()
+    }
+}
+
+pub const SAMPLE_RATE: u32 = 22050;
+
+fn squeal_wav<R: Rng>(rng: &mut R) -> Result<PathBuf, std::io::Error> {
+    const SECONDS: f32 = 1.5;
+
+    let samples = (SAMPLE_RATE as f32 * SECONDS) as u32;
+    let f1 = rng.gen_range(900.0..=1400.0);
+    let f2 = f1 + rng.gen_range(40.0..=120.0);
+
+    let mut data: Vec<i16> = Vec::with_capacity(samples as usize);
+    for n in 0..samples {
+        let t = n as f32 / SAMPLE_RATE as f32;
+        // two carriers slightly off frequency give the heterodyne whistle
+        let tone = (2.0 * std::f32::consts::PI * f1 * t).sin()
+            + (2.0 * std::f32::consts::PI * f2 * t).sin();
+        let noise: f32 = rng.gen_range(-0.3..=0.3);
+        data.push(((tone * 0.35 + noise) * i16::MAX as f32 * 0.8) as i16);
+    }
+
+    write_wav("elp_squeal.wav", &data)
+}
+
+/// Writes 16 bit mono samples at `SAMPLE_RATE` to a WAV file in the temp dir.
+pub fn write_wav(name: &str, samples: &[i16]) -> Result<PathBuf, std::io::Error> {
+    let mut data: Vec<u8> = Vec::with_capacity(samples.len() * 2);
+    for sample in samples {
+        data.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    let mut wav: Vec<u8> = Vec::with_capacity(44 + data.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&data);
+
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, wav)?;
+    Ok(path)
+}
+
+fn spawn_squeal<R: Rng>(config: &ConfigElp, rng: &mut R) -> Result<Option<Child>, ElpError> {
+    if config.backend == "silent" {
+        return Ok(None);
+    }
+
+    spawn_play(&squeal_wav(rng)?)
+}
+
+/// Plays a sound file without waiting for it to end.
+pub fn spawn_play(path: &std::path::Path) -> Result<Option<Child>, ElpError> {
+    use std::process::Command;
+
+    Command::new("afplay")
+        .arg(path)
+        .spawn()
+        .map(Some)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                ElpError::MissingTtsEngine("'afplay' command not found".to_string())
+            }
+            _ => ElpError::Io(e),
+        })
+}
+
+pub fn wait(child: Option<Child>) -> Result<(), std::io::Error> {
+    if let Some(mut child) = child {
+        child.wait()?;
+    }
+    Ok(())
+}
+
+fn transmit<R: Rng>(
+    msg: &str,
+    interference: Interference,
+    generator: &mut Generator<R>,
+) -> Result<Option<String>, ElpError> {
+    let config = generator.config().clone();
+
+    let first = spawn_say(
+        &prepare_message_for_say(msg, config.region),
+        &config.voice,
+        &config,
+    )?;
+
+    let mut other_msg = None;
+    let second = match interference {
+        Interference::Clean => None,
+        Interference::Overlap => {
+            let delay = generator.rng().gen_range(600..=1800);
+            thread::sleep(Duration::from_millis(delay));
+            let voice = get_other_voice(&config.voice, generator.rng());
+            let msg = generator.get_message("ANY");
+            let second = spawn_say(
+                &prepare_message_for_say(&msg, config.region),
+                &voice,
+                &config,
+            )?;
+            other_msg = Some(msg);
+            second
+        }
+        Interference::Squeal => {
+            let delay = generator.rng().gen_range(400..=1500);
+            thread::sleep(Duration::from_millis(delay));
+            spawn_squeal(&config, generator.rng())?
+        }
+    };
+
+    wait(first)?;
+    wait(second)?;
+    Ok(other_msg)
+}
+
+pub fn run_blocked_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let config = generator.config().clone();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let msg = generator.get_message(config.msg_type.as_str());
+        let interference = Interference::random(generator.rng());
+
+        let other_msg = transmit(&msg, interference, generator)?;
+
+        let answer = ask(&format!("[{}/{}] Your response: ", n, config.num_msg))?;
+        let correct = check_answer(interference, &msg, &answer);
+        if correct {
+            score += 1;
+        }
+
+        match interference {
+            Interference::Clean => println!("Transmission was clear."),
+            Interference::Overlap => println!(
+                "Transmission was blocked by: {}",
+                other_msg.unwrap_or_default().replace('!', "")
+            ),
+            Interference::Squeal => println!("Transmission was blocked by a carrier squeal."),
+        }
+        println!("{}", msg.replace('!', ""));
+        println!(
+            "{}",
+            if correct {
+                "Correct."
+            } else if interference.is_blocked() {
+                "Incorrect, you should have requested \"say again\"."
+            } else {
+                "Incorrect, the transmission was readable, read it back."
+            }
+        );
+        if !correct && !interference.is_blocked() {
+            for element in check_readback(&msg, &answer) {
+                println!("  missing or incorrect: {}", element);
+            }
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_is_say_again() {
+        use crate::blocked::is_say_again;
+
+        assert!(is_say_again("say again"));
+        assert!(is_say_again("A6KIA, say again"));
+        assert!(is_say_again("Station calling, SAY AGAIN please"));
+        assert!(!is_say_again("climb flight level 350, A6KIA"));
+        assert!(!is_say_again("again say"));
+    }
+
+    #[test]
+    fn test_check_answer() {
+        use crate::blocked::{check_answer, Interference};
+
+        let msg = "A6KIA, heading 270";
+        assert!(check_answer(Interference::Overlap, msg, "say again"));
+        assert!(check_answer(Interference::Squeal, msg, "say again"));
+        assert!(!check_answer(
+            Interference::Overlap,
+            msg,
+            "heading 270, A6KIA"
+        ));
+        assert!(check_answer(Interference::Clean, msg, "heading 270, A6KIA"));
+        assert!(!check_answer(
+            Interference::Clean,
+            msg,
+            "heading 250, A6KIA"
+        ));
+        assert!(!check_answer(Interference::Clean, msg, "asdf"));
+        assert!(!check_answer(Interference::Clean, msg, "say again"));
+        assert!(!check_answer(Interference::Clean, msg, ""));
+    }
+}
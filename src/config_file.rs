@@ -0,0 +1,242 @@
+use crate::elp_service::ConfigElp;
+use crate::error::ElpError;
+use crate::region::Region;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// One set of options, either the `[defaults]` table or a `[profiles.<name>]` table.
+/// Every key is optional, missing keys fall back to the built-in defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub callsign: Option<String>,
+    #[serde(rename = "type")]
+    pub msg_type: Option<String>,
+    pub count: Option<u32>,
+    pub rate: Option<u32>,
+    pub pause: Option<u32>,
+    pub comma: Option<bool>,
+    pub sayagain: Option<bool>,
+    pub voice: Option<String>,
+    pub mix: Option<Vec<String>>,
+    pub backend: Option<String>,
+    pub phrases: Option<Vec<String>>,
+    pub packs: Option<Vec<String>>,
+    pub region: Option<Region>,
+    pub transition_altitude: Option<u32>,
+}
+
+/// Layout of `config.toml`:
+///
+/// ```toml
+/// profile = "student-a6"
+///
+/// [defaults]
+/// rate = 160
+///
+/// [profiles.student-a6]
+/// callsign = "A6KIA"
+/// mix = ["heading", "flight_level", "squawk"]
+///
+/// [profiles.checkride]
+/// rate = 220
+/// pause = 2
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub defaults: Profile,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+impl Profile {
+    /// Copies the values set in `other` over `self`.
+    pub fn merge(&mut self, other: &Profile) {
+        let other = other.clone();
+        self.callsign = other.callsign.or(self.callsign.take());
+        self.msg_type = other.msg_type.or(self.msg_type.take());
+        self.count = other.count.or(self.count);
+        self.rate = other.rate.or(self.rate);
+        self.pause = other.pause.or(self.pause);
+        self.comma = other.comma.or(self.comma);
+        self.sayagain = other.sayagain.or(self.sayagain);
+        self.voice = other.voice.or(self.voice.take());
+        self.mix = other.mix.or(self.mix.take());
+        self.backend = other.backend.or(self.backend.take());
+        self.phrases = other.phrases.or(self.phrases.take());
+        self.packs = other.packs.or(self.packs.take());
+        self.region = other.region.or(self.region);
+        self.transition_altitude = other.transition_altitude.or(self.transition_altitude);
+    }
+
+    /// Applies the profile to `config`, skipping the options listed in `skip`
+    /// (those given explicitly on the command line).
+    pub fn apply(&self, config: &mut ConfigElp, skip: &[&str]) {
+        let keep = |id: &str| skip.contains(&id);
+
+        if let (Some(x), false) = (&self.callsign, keep("callsign")) {
+            config.call_sign = x.clone();
+        }
+        if let (Some(x), false) = (&self.msg_type, keep("type")) {
+            config.msg_type = x.clone();
+        }
+        if let (Some(x), false) = (self.count, keep("count")) {
+            config.num_msg = x;
+        }
+        if let (Some(x), false) = (self.rate, keep("rate")) {
+            config.rate = x;
+        }
+        if let (Some(x), false) = (self.pause, keep("pause")) {
+            config.pause = x;
+        }
+        if let (Some(x), false) = (self.comma, keep("comma")) {
+            config.comma = x;
+        }
+        if let (Some(x), false) = (self.sayagain, keep("sayagain")) {
+            config.sayagain = x;
+        }
+        if let (Some(x), false) = (&self.voice, keep("voice")) {
+            config.voice = x.clone();
+        }
+        if let (Some(x), false) = (&self.mix, keep("mix")) {
+            config.mix = x.clone();
+        }
+        if let (Some(x), false) = (&self.backend, keep("backend")) {
+            config.backend = x.clone();
+        }
+        if let (Some(x), false) = (&self.phrases, keep("phrases")) {
+            config.phrases = x.clone();
+        }
+        if let (Some(x), false) = (&self.packs, keep("pack")) {
+            config.packs = x.clone();
+        }
+        if let (Some(x), false) = (self.region, keep("region")) {
+            config.region = x;
+        }
+        if let (Some(x), false) = (self.transition_altitude, keep("transition-altitude")) {
+            config.transition_altitude = Some(x);
+        }
+    }
+}
+
+impl ConfigFile {
+    pub fn parse(text: &str) -> Result<ConfigFile, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<ConfigFile, ElpError> {
+        let data_error = |message: String| ElpError::DataFile {
+            path: path.to_path_buf(),
+            message,
+        };
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| data_error(format!("can't read config file: {}", e)))?;
+        ConfigFile::parse(&text).map_err(|e| data_error(format!("invalid config file: {}", e)))
+    }
+
+    /// Returns `[defaults]` merged with the requested profile, or with the
+    /// file's own `profile` key when `name` is `None`.
+    pub fn resolve(&self, name: Option<&str>) -> Result<Profile, ElpError> {
+        let mut profile = self.defaults.clone();
+
+        if let Some(name) = name.or(self.profile.as_deref()) {
+            match self.profiles.get(name) {
+                Some(selected) => profile.merge(selected),
+                None => {
+                    let mut known: Vec<&str> = self.profiles.keys().map(|k| k.as_str()).collect();
+                    known.sort();
+                    return Err(ElpError::InvalidArgument(format!(
+                        "unknown profile '{}', known profiles: {}",
+                        name,
+                        known.join(", ")
+                    )));
+                }
+            }
+        }
+
+        Ok(profile)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/elp/config.toml`, falling back to `~/.config/elp/config.toml`.
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("elp").join("config.toml"))
+}
+
+/// `$XDG_DATA_HOME/elp`, falling back to `~/.local/share/elp`.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?)
+            .join(".local")
+            .join("share"),
+    };
+    Some(base.join("elp"))
+}
+
+pub fn to_toml(config: &ConfigElp) -> String {
+    toml::to_string(config).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    const CONFIG: &str = r#"
+profile = "student-a6"
+
+[defaults]
+rate = 160
+pause = 3
+
+[profiles.student-a6]
+callsign = "A6KIA"
+mix = ["heading", "squawk"]
+
+[profiles.checkride]
+rate = 220
+backend = "silent"
+"#;
+
+    #[test]
+    fn test_resolve_profile() {
+        use crate::config_file::ConfigFile;
+
+        let file = ConfigFile::parse(CONFIG).unwrap();
+
+        let default = file.resolve(None).unwrap();
+        assert_eq!(default.callsign.as_deref(), Some("A6KIA"));
+        assert_eq!(default.rate, Some(160));
+
+        let checkride = file.resolve(Some("checkride")).unwrap();
+        assert_eq!(checkride.callsign, None);
+        assert_eq!(checkride.rate, Some(220));
+        assert_eq!(checkride.pause, Some(3));
+
+        assert!(file.resolve(Some("unknown")).is_err());
+        assert!(ConfigFile::parse("colour = 1").is_err());
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        use crate::config_file::ConfigFile;
+        use crate::elp_service::ConfigElp;
+
+        let file = ConfigFile::parse(CONFIG).unwrap();
+        let profile = file.resolve(None).unwrap();
+
+        let mut config = ConfigElp::default();
+        profile.apply(&mut config, &["rate"]);
+
+        assert_eq!(config.call_sign, "A6KIA");
+        assert_eq!(config.pause, 3);
+        assert_eq!(config.rate, 180);
+        assert_eq!(config.mix, vec!["heading", "squawk"]);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::pilot::get_pilot_report;
+use crate::readback::check_readback;
+use crate::region::Region;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+pub const REQUESTS: [&str; 5] = ["start-up", "climb", "position", "direct", "descent"];
+
+/// A pilot transmission and the controller response expected for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PilotCall {
+    pub text: String,
+    /// Response shown after the answer.
+    pub response: String,
+    /// Parts the typed response must contain, the first one is the call sign.
+    pub elements: Vec<String>,
+}
+
+impl PilotCall {
+    /// Elements missing from the typed response.
+    pub fn check(&self, answer: &str) -> Vec<String> {
+        check_readback(&self.elements.join(", "), answer)
+    }
+}
+
+/// Two different flight levels, the lower one first.
+fn flight_levels<R: Rng>(generator: &mut Generator<R>) -> (String, String) {
+    let level = |fl: &str| -> u32 { fl.replace("flight level ", "").parse().unwrap() };
+    loop {
+        let (a, b) = (generator.get_fl(), generator.get_fl());
+        if level(&a) < level(&b) {
+            return (a, b);
+        } else if level(&a) > level(&b) {
+            return (b, a);
+        }
+    }
+}
+
+fn call(text: String, elements: Vec<String>) -> PilotCall {
+    PilotCall {
+        text,
+        response: elements.join(", ").replace('!', ""),
+        elements,
+    }
+}
+
+pub fn get_pilot_call<R: Rng>(kind: &str, generator: &mut Generator<R>) -> Option<PilotCall> {
+    let call_sign = generator.config().call_sign.clone();
+    let region = generator.config().region;
+
+    let pilot_call = match kind {
+        "start-up" => {
+            let airport = generator.get_slot("airport").unwrap();
+            let letter = generator.get_slot("letter").unwrap();
+            call(
+                format!(
+                    "{} ground, {}, stand {}, information {}, request start-up",
+                    airport,
+                    call_sign,
+                    generator.rng().gen_range(1..=40),
+                    letter
+                ),
+                vec![call_sign, "start-up approved".to_string()],
+            )
+        }
+        "climb" => {
+            let (current, requested) = flight_levels(generator);
+            let climb = match region {
+                Region::Faa => "climb and maintain",
+                Region::Icao | Region::Cap413 => "climb",
+            };
+            call(
+                format!("{}, {}, request climb {}", call_sign, current, requested),
+                vec![call_sign, format!("{} {}", climb, requested)],
+            )
+        }
+        "position" => call(
+            get_pilot_report("position_report", generator).unwrap().text,
+            vec![call_sign, "roger".to_string()],
+        ),
+        "direct" => {
+            let waypoint = generator.get_waypoint();
+            call(
+                format!("{}, request direct {}", call_sign, waypoint),
+                vec![call_sign, "proceed direct".to_string(), waypoint],
+            )
+        }
+        "descent" => {
+            let (requested, current) = flight_levels(generator);
+            let descend = match region {
+                Region::Faa => "descend and maintain",
+                Region::Icao | Region::Cap413 => "descend",
+            };
+            call(
+                format!("{}, {}, request descent {}", call_sign, current, requested),
+                vec![call_sign, format!("{} {}", descend, requested)],
+            )
+        }
+        _ => return None,
+    };
+    Some(pilot_call)
+}
+
+/// The trainee plays the controller: pilot calls are spoken in the `Pilot`
+/// voice and the typed response is checked against the expected elements.
+/// `kinds` selects the requests, empty means all of them.
+pub fn run_controller_session<R: Rng>(
+    generator: &mut Generator<R>,
+    kinds: &[String],
+) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    config.voice = "Pilot".to_string();
+    let kinds: Vec<&str> = REQUESTS
+        .iter()
+        .filter(|kind| kinds.is_empty() || kinds.iter().any(|k| k == *kind))
+        .cloned()
+        .collect();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let kind = kinds.choose(generator.rng()).unwrap();
+        let pilot_call = get_pilot_call(kind, generator).unwrap();
+        say(
+            prepare_message_for_say(&pilot_call.text, config.region),
+            false,
+            &mut config,
+        )?;
+
+        let answer = ask(&format!("[{}/{}] Controller: ", n, config.num_msg))?;
+        let missing = pilot_call.check(&answer);
+
+        println!("Pilot: {}", pilot_call.text.replace('!', ""));
+        println!("Expected: {}", pilot_call.response);
+        if missing.is_empty() {
+            println!("Correct.");
+            score += 1;
+        } else {
+            for element in missing {
+                println!("  missing or incorrect: {}", element.replace('!', ""));
+            }
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_pilot_calls() {
+        use crate::controller::{get_pilot_call, REQUESTS};
+        use crate::elp_service::{ConfigElp, Generator};
+
+        let config = ConfigElp {
+            call_sign: "A6KIA".to_string(),
+            ..Default::default()
+        };
+        let mut generator = Generator::new(config, rand::thread_rng());
+
+        for kind in REQUESTS {
+            let pilot_call = get_pilot_call(kind, &mut generator).unwrap();
+            assert!(pilot_call.text.contains("A6KIA"));
+            assert!(pilot_call.check(&pilot_call.response).is_empty());
+            assert_eq!(pilot_call.check("").len(), pilot_call.elements.len());
+        }
+
+        let climb = get_pilot_call("climb", &mut generator).unwrap();
+        let requested = climb.text.split("request climb ").nth(1).unwrap();
+        assert_eq!(climb.response, format!("A6KIA, climb {}", requested));
+
+        let descent = get_pilot_call("descent", &mut generator).unwrap();
+        let requested = descent.text.split("request descent ").nth(1).unwrap();
+        assert_eq!(descent.response, format!("A6KIA, descend {}", requested));
+        assert_eq!(descent.check("A6KIA, descend").len(), 1);
+        assert_eq!(descent.check("A6KIA, descend flight level 460").len(), 1);
+
+        let direct = get_pilot_call("direct", &mut generator).unwrap();
+        assert_eq!(direct.check("A6KIA, proceed direct").len(), 1);
+        assert!(get_pilot_call("taxi", &mut generator).is_none());
+    }
+}
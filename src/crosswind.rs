@@ -0,0 +1,138 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Knots an answer may be off.
+pub const TOLERANCE: i32 = 2;
+
+/// Crosswind limits asked about, in knots.
+const LIMITS: [u32; 6] = [10, 15, 20, 25, 30, 35];
+
+/// Headwind (negative for a tailwind) and crosswind components in knots,
+/// rounded, for a wind from `direction` at `speed` on a runway heading.
+pub fn components(direction: u32, speed: u32, runway_heading: u32) -> (i32, u32) {
+    let angle = (direction as f64 - runway_heading as f64).to_radians();
+    let headwind = (speed as f64 * angle.cos()).round() as i32;
+    let crosswind = (speed as f64 * angle.sin()).abs().round() as u32;
+    (headwind, crosswind)
+}
+
+/// First number in an answer, "-5", "tailwind 5" or "5 kt".
+fn parse_knots(answer: &str) -> Option<i32> {
+    let knots: i32 = answer
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .find_map(|word| word.parse().ok())?;
+    if answer.contains("tail") {
+        Some(-knots.abs())
+    } else {
+        Some(knots)
+    }
+}
+
+/// A landing clearance or the ATIS is spoken, the trainee works out the
+/// headwind or crosswind component or whether the crosswind is within a
+/// limit. Components count within TOLERANCE knots, a limit that close to
+/// the crosswind accepts both answers.
+pub fn run_crosswind_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        // components need a wind with a direction
+        let (msg, wind, direction) = loop {
+            let msg = if generator.rng().gen_bool(0.5) {
+                generator.get_message("ATIS")
+            } else {
+                generator.new_surface();
+                let wind = generator.get_wind();
+                let runway = generator.get_rw();
+                format!(
+                    "{}, {}, {}, cleared to land",
+                    config.call_sign, wind, runway
+                )
+            };
+            let wind = generator.surface_wind();
+            if let Some(direction) = wind.direction {
+                break (msg, wind, direction);
+            }
+        };
+        let runway = generator.get_runway();
+        let (headwind, crosswind) = components(direction, wind.speed, runway.heading());
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+
+        let prompt = |question: &str| format!("[{}/{}] {}: ", n, config.num_msg, question);
+        let correct = match generator.rng().gen_range(0..3) {
+            0 => {
+                let answer = ask(&prompt("Headwind component, knots (negative for tailwind)"))?;
+                parse_knots(&answer).is_some_and(|knots| (knots - headwind).abs() <= TOLERANCE)
+            }
+            1 => {
+                let answer = ask(&prompt("Crosswind component, knots"))?;
+                parse_knots(&answer)
+                    .is_some_and(|knots| (knots.abs() - crosswind as i32).abs() <= TOLERANCE)
+            }
+            _ => {
+                let limit = *LIMITS.choose(generator.rng()).unwrap();
+                let answer = ask(&prompt(&format!(
+                    "Crosswind limit {} knots, within limits (yes/no)",
+                    limit
+                )))?;
+                let within = crosswind <= limit;
+                let close = (crosswind as i32 - limit as i32).abs() <= TOLERANCE;
+                match answer.to_lowercase().as_str() {
+                    "yes" | "y" => within || close,
+                    "no" | "n" => !within || close,
+                    _ => false,
+                }
+            }
+        };
+
+        println!("{}", msg.replace('!', ""));
+        println!(
+            "  {} {}: {} {} knots, crosswind {} knots",
+            runway.designator(),
+            wind.metar(),
+            if headwind < 0 { "tailwind" } else { "headwind" },
+            headwind.abs(),
+            crosswind
+        );
+        if correct {
+            println!("Correct.");
+            score += 1;
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_components() {
+        use crate::crosswind::components;
+
+        assert_eq!(components(270, 20, 270), (20, 0));
+        assert_eq!(components(300, 20, 270), (17, 10));
+        assert_eq!(components(360, 15, 270), (0, 15));
+        assert_eq!(components(90, 10, 270), (-10, 0));
+        assert_eq!(components(10, 20, 340), (17, 10));
+    }
+
+    #[test]
+    fn test_parse_knots() {
+        use crate::crosswind::parse_knots;
+
+        assert_eq!(parse_knots("17"), Some(17));
+        assert_eq!(parse_knots("-5"), Some(-5));
+        assert_eq!(parse_knots("tailwind 5 kt"), Some(-5));
+        assert_eq!(parse_knots("no idea"), None);
+    }
+}
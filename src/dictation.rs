@@ -0,0 +1,206 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::parser::tokens;
+use crate::phonetic::character;
+use rand::Rng;
+
+/// One step of the word alignment of a dictation with its message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diff {
+    Same(String),
+    Missing(String),
+    Extra(String),
+    Changed { expected: String, typed: String },
+}
+
+/// Words compared the way `parser::tokens` reads them, so "350", "3 5 0"
+/// and "tree fife zero" are all "350" and "5 thousand 5 hundred" is "5500".
+/// Spelled or single letters are joined: "delta lima hotel" is "dlh".
+pub fn words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut spelling = false;
+    for token in tokens(text) {
+        let letter = match character(&token) {
+            Some(c) if c.is_ascii_alphabetic() => Some(c.to_ascii_lowercase()),
+            _ if token.len() == 1 && token.chars().all(|c| c.is_alphabetic()) => {
+                token.chars().next()
+            }
+            _ => None,
+        };
+        match (letter, words.last_mut()) {
+            (Some(c), Some(last)) if spelling => last.push(c),
+            (Some(c), _) => words.push(c.to_string()),
+            (None, _) => words.push(token),
+        }
+        spelling = letter.is_some();
+    }
+    words
+}
+
+/// Word-level alignment with the fewest changes.
+pub fn align(expected: &str, typed: &str) -> Vec<Diff> {
+    let (a, b) = (words(expected), words(typed));
+    // cost[i][j]: changes to turn a[i..] into b[j..]
+    let mut cost = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..=a.len()).rev() {
+        for j in (0..=b.len()).rev() {
+            cost[i][j] = if i == a.len() {
+                b.len() - j
+            } else if j == b.len() {
+                a.len() - i
+            } else {
+                let change = if a[i] == b[j] { 0 } else { 1 };
+                (cost[i + 1][j + 1] + change)
+                    .min(cost[i + 1][j] + 1)
+                    .min(cost[i][j + 1] + 1)
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] && cost[i][j] == cost[i + 1][j + 1] {
+            diff.push(Diff::Same(a[i].clone()));
+            (i, j) = (i + 1, j + 1);
+        } else if i < a.len() && j < b.len() && cost[i][j] == cost[i + 1][j + 1] + 1 {
+            diff.push(Diff::Changed {
+                expected: a[i].clone(),
+                typed: b[j].clone(),
+            });
+            (i, j) = (i + 1, j + 1);
+        } else if i < a.len() && cost[i][j] == cost[i + 1][j] + 1 {
+            diff.push(Diff::Missing(a[i].clone()));
+            i += 1;
+        } else {
+            diff.push(Diff::Extra(b[j].clone()));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// Share of the expected words typed correctly, in percent.
+pub fn accuracy(diff: &[Diff]) -> f64 {
+    let expected = diff.iter().filter(|d| !matches!(d, Diff::Extra(_))).count();
+    let same = diff.iter().filter(|d| matches!(d, Diff::Same(_))).count();
+    if expected == 0 {
+        100.0
+    } else {
+        same as f64 * 100.0 / expected as f64
+    }
+}
+
+fn colour(text: &str, code: u8) -> String {
+    if std::env::var_os("NO_COLOR").is_some() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+}
+
+/// Correct words in green, missing ones in red in brackets, extra ones in
+/// yellow, changed ones as "typed->expected".
+pub fn render(diff: &[Diff]) -> String {
+    diff.iter()
+        .map(|d| match d {
+            Diff::Same(word) => colour(word, 32),
+            Diff::Missing(word) => colour(&format!("[{}]", word), 31),
+            Diff::Extra(word) => colour(&format!("+{}", word), 33),
+            Diff::Changed { expected, typed } => {
+                format!("{}->{}", colour(typed, 33), colour(expected, 31))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// The trainee types each message word for word, a message counts when
+/// every word is right.
+pub fn run_dictation_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let mut total_accuracy = 0.0;
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let msg = generator.get_message(&config.msg_type);
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+
+        let answer = ask(&format!("[{}/{}] Dictation: ", n, config.num_msg))?;
+        let diff = align(&msg, &answer);
+        let accuracy = accuracy(&diff);
+        total_accuracy += accuracy;
+
+        println!("{}", msg.replace('!', ""));
+        println!("{}", render(&diff));
+        println!("Accuracy: {:.0}%", accuracy);
+        if diff.iter().all(|d| matches!(d, Diff::Same(_))) {
+            score += 1;
+        }
+    }
+
+    println!(
+        "Score: {}/{}, accuracy {:.0}%",
+        score,
+        config.num_msg,
+        total_accuracy / config.num_msg.max(1) as f64
+    );
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_words() {
+        use crate::dictation::words;
+
+        assert_eq!(
+            words("climb flight level 350"),
+            words("Climb Flight Level tree fife zero")
+        );
+        assert_eq!(
+            words("contact 118.725"),
+            words("contact one one eight decimal seven two five")
+        );
+        assert_eq!(
+            words("A6KIA, squawk 4521"),
+            ["a", "6", "kia", "squawk", "4521"]
+        );
+        assert_eq!(words("alfa six kilo india alfa"), words("A6KIA"));
+        assert_eq!(words("DLHAB"), words("delta lima hotel alfa bravo"));
+        assert_eq!(
+            words("altitude 5 thousand 5 hundred feet"),
+            words("altitude 5500 feet")
+        );
+    }
+
+    #[test]
+    fn test_align() {
+        use crate::dictation::{accuracy, align, Diff};
+
+        let diff = align(
+            "A6KIA, climb flight level 350",
+            "A6KIA climb level tree five one",
+        );
+        assert!(diff.contains(&Diff::Missing("flight".to_string())));
+        assert!(diff.contains(&Diff::Changed {
+            expected: "350".to_string(),
+            typed: "351".to_string()
+        }));
+        assert_eq!(
+            diff.iter().filter(|d| matches!(d, Diff::Same(_))).count(),
+            5
+        );
+        assert!((accuracy(&diff) - 71.4).abs() < 0.1);
+
+        let diff = align("turn left", "turn left now");
+        assert_eq!(diff.last(), Some(&Diff::Extra("now".to_string())));
+        assert_eq!(accuracy(&diff), 100.0);
+    }
+}
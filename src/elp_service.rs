@@ -1,20 +1,37 @@
-use once_cell::sync::Lazy;
+use crate::error::ElpError;
+use crate::frequency;
+use crate::region::Region;
+use crate::runway::{Airport, Runway, AIRPORTS};
+use crate::template::{Part, PhraseBook, Template};
+use crate::transponder;
+use crate::waypoint;
+use crate::wind::{self, Wind};
 use rand::seq::SliceRandom;
 use rand::Rng;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::process::Output;
-use std::sync::Mutex;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ConfigElp {
+    #[serde(rename = "callsign")]
     pub call_sign: String,
+    #[serde(rename = "type")]
     pub msg_type: String,
+    #[serde(rename = "count")]
     pub num_msg: u32,
     pub rate: u32,
     pub pause: u32,
     pub comma: bool,
     pub sayagain: bool,
     pub voice: String,
+    pub mix: Vec<String>,
+    pub backend: String,
+    pub phrases: Vec<String>,
+    pub packs: Vec<String>,
+    pub region: Region,
+    /// Transition altitude in feet, `None` for the default of the region.
+    pub transition_altitude: Option<u32>,
 }
 
 impl ConfigElp {
@@ -28,6 +45,12 @@ impl ConfigElp {
         comma: bool,
         sayagain: bool,
         voice: String,
+        mix: Vec<String>,
+        backend: String,
+        phrases: Vec<String>,
+        packs: Vec<String>,
+        region: Region,
+        transition_altitude: Option<u32>,
     ) -> ConfigElp {
         ConfigElp {
             call_sign,
@@ -38,6 +61,12 @@ impl ConfigElp {
             comma,
             sayagain,
             voice,
+            mix,
+            backend,
+            phrases,
+            packs,
+            region,
+            transition_altitude,
         }
     }
     pub fn parse_bool(x: u32) -> bool {
@@ -45,23 +74,25 @@ impl ConfigElp {
     }
 }
 
-pub static CONFIG: Lazy<Mutex<ConfigElp>> = Lazy::new(|| {
-    let m = ConfigElp::new(
-        "A6KIB".to_string(),
-        "ANY".to_string(),
-        0,
-        180,
-        5,
-        false,
-        false,
-        "ATC0".to_string(),
-    );
-    Mutex::new(m)
-});
-
-pub fn clone_config() -> ConfigElp {
-    let config = CONFIG.lock().unwrap();
-    config.clone()
+impl Default for ConfigElp {
+    fn default() -> ConfigElp {
+        ConfigElp::new(
+            "A6KIB".to_string(),
+            "ANY".to_string(),
+            0,
+            180,
+            5,
+            false,
+            false,
+            "ATC0".to_string(),
+            Vec::new(),
+            "say".to_string(),
+            Vec::new(),
+            Vec::new(),
+            Region::Icao,
+            None,
+        )
+    }
 }
 
 const MAC_VOICES: [(&str, &str); 10] = [
@@ -119,357 +150,577 @@ const ALFABET: [(&str, &str); 39] = [
     (".", "decimal"),
 ];
 
-const MESSAGES: [(&str, u32); 9] = [
-    ("*", 1), // flight level
-    ("^", 2), // heading
-    ("&", 3), // squawk
-    ("=", 4), // runway
-    ("@", 5), // 117.975 – 137.000 MHz (VHF Aeronautical communications)
-    ("!", 6), // QNH
-    ("$", 7), // altitude
-    ("-", 8), // wind
-    ("+", 9), // any message
+// items of ANY messages, selectable with --mix
+const MESSAGES: [(&str, &str); 9] = [
+    ("flight_level", "{flight_level}"),
+    ("heading", "{heading}"),
+    ("squawk", "{squawk}"),
+    ("runway", "{runway}"),
+    ("frequency", "{contact}"), // station and one of its channels, 25 and 8.33 kHz
+    ("qnh", "{qnh}"),
+    ("altitude", "{altitude}"),
+    ("wind", "{wind}"),
+    ("clearance", "{clearance}"), // any message from the clearance phrases
 ];
 
-const LR: [&str; 2] = ["left", "right"];
-
-pub fn get_frequency() -> String {
-    let fq_msg = [
-        "departure",
-        "berlin tower",
-        "apron",
-        "dubai information",
-        "munich approach",
-        "munich tower",
-        "ground",
-        "fujairah information",
-    ];
-
-    let mut rng = rand::thread_rng();
-    let mut x = rng.gen_range(117975..=137000);
-    x = (x / 5) * 5;
-    let mut s_fq = format!("{:03}.{:03}", x / 1000, x % 1000);
-    s_fq = s_fq.trim_end_matches('0').trim_end_matches('.').to_string();
-
-    format!(
-        "contact {} {}",
-        fq_msg[rng.gen_range(0..=(fq_msg.len() - 1))],
-        s_fq
-    )
-    .to_string()
-    .trim()
-    .to_string()
+pub const BACKENDS: [&str; 2] = ["say", "silent"];
+
+pub fn get_mix_names() -> Vec<&'static str> {
+    MESSAGES.iter().map(|(name, _)| *name).collect()
 }
 
-pub fn get_rw() -> String {
-    let mut rng = rand::thread_rng();
-    format!(
-        "runway {:02} {}",
-        rng.gen_range(10..=360) / 10,
-        LR[rng.gen_range(0..=1)]
-    )
-    .to_string()
-    .trim()
-    .to_string()
+/// Message generator, owns its configuration and random number generator
+/// so several generators can run side by side without shared state.
+pub struct Generator<R: Rng> {
+    config: ConfigElp,
+    rng: R,
+    phrases: PhraseBook,
+    surface: Option<Surface>,
 }
 
-pub fn get_wind() -> String {
-    let mut rng = rand::thread_rng();
-    format!(
-        "wind {:03} degree {} knots",
-        (rng.gen_range(10..=360) / 10) * 10,
-        rng.gen_range(1..=50)
-    )
-    .to_string()
-    .trim()
-    .to_string()
+/// Airport and surface wind shared by the slots of one message, so the
+/// runway in use fits the wind.
+#[derive(Clone, Copy, Debug)]
+struct Surface {
+    airport: &'static Airport,
+    wind: Wind,
+    runway: Option<Runway>,
 }
 
-pub fn get_squawk() -> String {
-    const VFR_1: u32 = 2000;
-    const VFR_2: u32 = 7000;
-    const HIJACK: u32 = 7500;
-    const RADIO_FAIL: u32 = 7600;
-    const EMERGENCY: u32 = 7700;
-
-    let forbidden = [VFR_1, VFR_2, HIJACK, RADIO_FAIL, EMERGENCY];
-    let mut squawk_tmp;
-    let mut rng = rand::thread_rng();
-
-    loop {
-        squawk_tmp = rng.gen_range(2000..=9999);
-        if !forbidden.contains(&squawk_tmp) {
-            break;
+impl<R: Rng> Generator<R> {
+    pub fn new(config: ConfigElp, rng: R) -> Generator<R> {
+        Generator {
+            rng,
+            phrases: PhraseBook::builtin_for(config.region),
+            config,
+            surface: None,
         }
     }
-    format!("squawk {:04}", squawk_tmp)
-        .to_string()
-        .trim()
-        .to_string()
-}
 
-pub fn get_fl() -> String {
-    let mut rng = rand::thread_rng();
-    let x = rng.gen_range(60..=420);
+    pub fn phrases(&self) -> &PhraseBook {
+        &self.phrases
+    }
 
-    format!("flight level {}", (x / 5) * 5)
-        .to_string()
-        .trim()
-        .to_string()
-}
+    pub fn phrases_mut(&mut self) -> &mut PhraseBook {
+        &mut self.phrases
+    }
 
-pub fn get_altitude() -> String {
-    let mut rng = rand::thread_rng();
+    /// Adds the phrase packs and files listed in the configuration to the
+    /// built-in phrases.
+    pub fn load_phrases(&mut self) -> Result<(), ElpError> {
+        for name in self.config.packs.clone() {
+            let pack = crate::packs::find_pack(&name)?;
+            if pack.info.region != self.config.region.name() {
+                return Err(ElpError::InvalidArgument(format!(
+                    "phrase pack '{}' is for {} phraseology, the region is {}",
+                    name,
+                    pack.info.region,
+                    self.config.region.name()
+                )));
+            }
+            self.phrases.merge(pack.phrases);
+        }
+        for path in self.config.phrases.clone() {
+            let path = std::path::PathBuf::from(path);
+            self.phrases.merge(PhraseBook::load(&path)?);
+            if let Some(slot) = self.phrases.unknown_slots().first() {
+                return Err(ElpError::DataFile {
+                    path,
+                    message: format!("unknown slot in {}", slot),
+                });
+            }
+        }
 
-    let mut hundreds: u32 = 0;
-    let mut thousands: u32 = 0;
+        if !self.get_message_types().contains(&self.config.msg_type) {
+            return Err(ElpError::InvalidArgument(format!(
+                "unknown message type '{}', known types: {}",
+                self.config.msg_type,
+                self.get_message_types().join(", ")
+            )));
+        }
+        Ok(())
+    }
 
-    let alfabet_map: HashMap<&str, &str> = ALFABET.iter().cloned().collect();
+    pub fn config(&self) -> &ConfigElp {
+        &self.config
+    }
 
-    let x = (rng.gen_range(500..=42000) / 100) * 100;
-    if x > 1000 {
-        thousands = x / 1000;
+    pub fn config_mut(&mut self) -> &mut ConfigElp {
+        &mut self.config
     }
-    if x > 1000 && (x - (thousands * 1000)) > 0 {
-        hundreds = (x - (thousands * 1000)) / 100
+
+    pub fn rng(&mut self) -> &mut R {
+        &mut self.rng
     }
-    let mut alt_msg = String::new();
-    if thousands > 0 {
-        alt_msg = format!(
-            "{} {} {}",
-            alt_msg,
-            thousands,
-            alfabet_map.get("000").unwrap()
-        )
-        .to_string();
-    }
-    if hundreds > 0 {
-        alt_msg = format!(
-            "{} {} {}",
-            alt_msg,
-            hundreds,
-            alfabet_map.get("00").unwrap()
-        )
-        .to_string();
+
+    pub fn get_station(&mut self) -> String {
+        let fq_msg = [
+            "departure",
+            "berlin tower",
+            "apron",
+            "dubai information",
+            "munich approach",
+            "munich tower",
+            "ground",
+            "fujairah information",
+        ];
+
+        fq_msg[self.rng.gen_range(0..=(fq_msg.len() - 1))].to_string()
     }
-    format!("altitude{} feet", alt_msg).to_string()
-}
 
-pub fn get_heading() -> String {
-    let mut rng = rand::thread_rng();
+    /// Waypoint name, "!" keeps it from being spelled letter by letter.
+    pub fn get_waypoint(&mut self) -> String {
+        format!("!{}", waypoint::waypoint_name(&mut self.rng))
+    }
 
-    let x = (rng.gen_range(10..=360) / 10) * 10;
+    /// Channel anywhere in the band as said on the radio, see `frequency::Channel`.
+    pub fn get_frequency_value(&mut self) -> String {
+        frequency::random_channel("any", self.config.region, &mut self.rng).text(self.config.region)
+    }
 
-    format!("heading {:03}", x).to_string().trim().to_string()
-}
+    pub fn get_frequency(&mut self) -> String {
+        let station_type = ["ground", "tower", "approach", "departure", "information"]
+            .choose(&mut self.rng)
+            .unwrap();
+        self.get_frequency_for(station_type)
+    }
 
-pub fn get_qnh(imperial: bool) -> String {
-    let mut rng = rand::thread_rng();
+    /// "contact munich tower 118.705" for a station of the type.
+    pub fn get_frequency_for(&mut self, station_type: &str) -> String {
+        let (station, channel) =
+            frequency::station_channel(station_type, self.config.region, &mut self.rng);
+        format!("contact {} {}", station, channel.text(self.config.region))
+    }
 
-    let qnh: String = if !imperial || rng.gen_range(0..=1) == 0 {
-        let x = rng.gen_range(931..=1066) as u32;
-        let qnh = format!("qnh {} hectopascals", x);
-        qnh
-    } else {
-        let x = rng.gen_range(2750..=3150) as f32;
-        let qnh = format!("qnh {:.02} inches", x / 100.0);
-        qnh
-    };
+    fn surface(&mut self) -> Surface {
+        match self.surface {
+            Some(surface) => surface,
+            None => {
+                let surface = Surface {
+                    airport: AIRPORTS.choose(&mut self.rng).unwrap(),
+                    wind: Wind::random(&mut self.rng),
+                    runway: None,
+                };
+                self.surface = Some(surface);
+                surface
+            }
+        }
+    }
 
-    qnh.trim().to_string()
-}
+    /// Starts a new airport and wind, every message does so.
+    pub fn new_surface(&mut self) {
+        self.surface = None;
+    }
 
-pub fn get_temperature() -> String {
-    let mut rng = rand::thread_rng();
-
-    let mut temperature = rng.gen_range(-30..=50);
-    let mut dew_point = rng.gen_range((temperature - 15)..=temperature);
-    let mut minus_temp = "".to_string();
-    let mut minus_dp = "".to_string();
-    if temperature < 0 {
-        minus_temp = "minus".to_string();
-        temperature = 0 - temperature;
-    }
-    if dew_point < 0 {
-        minus_dp = "minus".to_string();
-        dew_point = 0 - dew_point;
-    }
-
-    format!(
-        "temperature {} {} dew point {} {}",
-        minus_temp, temperature, minus_dp, dew_point
-    )
-    .to_string()
-    .replace("  ", " ")
-    .trim()
-    .to_string()
-}
+    /// Surface wind of the current message.
+    pub fn surface_wind(&mut self) -> Wind {
+        self.surface().wind
+    }
 
-pub fn get_random_msg() -> String {
-    let any_msg = [
-        "engine start-up approved",
-        "cleared to munich via T1B departure",
-        "cleared to lyon via M2F departure",
-        "hold short =, taxi via LFM",
-    ];
+    /// Runway in use at the current airport for the current wind, the same
+    /// one for the whole message. Any runway will do with a calm or
+    /// variable wind.
+    pub fn get_runway(&mut self) -> Runway {
+        let mut surface = self.surface();
+        let runway = match surface.runway {
+            Some(runway) => runway,
+            None => {
+                let direction = match surface.wind.direction {
+                    Some(direction) => direction,
+                    None => self.rng.gen_range(1..=36) * 10,
+                };
+                surface.airport.active_runway(direction, &mut self.rng)
+            }
+        };
+        surface.runway = Some(runway);
+        self.surface = Some(surface);
+        runway
+    }
 
-    let mut rng = rand::thread_rng();
+    pub fn get_rw(&mut self) -> String {
+        self.get_runway().text(self.config.region)
+    }
 
-    let mut any_tmp = "".to_string();
-    let mut found = "".to_string();
+    /// Surface wind as spoken, see `wind::Wind::text`.
+    pub fn get_wind(&mut self) -> String {
+        self.surface_wind().text(self.config.region)
+    }
+
+    /// Discrete code assigned to an IFR flight, see `transponder::assign_code`.
+    pub fn get_squawk(&mut self) -> String {
+        let code = transponder::assign_code("ifr", self.config.region, &mut self.rng).unwrap();
+        format!("squawk {:04}", code)
+    }
 
-    let alfabet_map: HashMap<&str, &str> = ALFABET.iter().cloned().collect();
+    /// Transition altitude in feet and transition level: levels up to the
+    /// altitude are given in feet, from the level up as flight levels. The
+    /// transition level is the first flight level in tens at least 1000 feet
+    /// above the altitude, the FAA uses FL180 over 18000 feet.
+    pub fn transition(&self) -> (u32, u32) {
+        let region = self.config.region;
+        let altitude = self
+            .config
+            .transition_altitude
+            .unwrap_or(region.transition_altitude());
+        let level = match region {
+            Region::Faa => altitude.div_ceil(1000) * 10,
+            Region::Icao | Region::Cap413 => altitude.div_ceil(1000) * 10 + 10,
+        };
+        (altitude, level)
+    }
+
+    pub fn get_fl(&mut self) -> String {
+        let (_, transition_level) = self.transition();
+        let x = self.rng.gen_range(transition_level..=420);
+
+        format!("flight level {}", (x / 5) * 5)
+            .to_string()
+            .trim()
+            .to_string()
+    }
+
+    pub fn get_altitude(&mut self) -> String {
+        let (transition_altitude, _) = self.transition();
+        let x = (self.rng.gen_range(500..=transition_altitude) / 100) * 100;
+        self.altitude_text(x)
+    }
+
+    /// "flight level 250" above the transition altitude, else the altitude.
+    pub fn level_text(&self, feet: u32) -> String {
+        let (transition_altitude, _) = self.transition();
+        if feet > transition_altitude {
+            format!("flight level {}", feet / 100)
+        } else {
+            self.altitude_text(feet)
+        }
+    }
 
-    for mut _symbol in any_msg[rng.gen_range(0..=any_msg.len() - 1)].chars() {
-        if alfabet_map.contains_key(_symbol.to_string().as_str()) {
-            if _symbol != 'T' && !_symbol.is_ascii_digit() {
-                _symbol = rng.gen_range('A'..='Z');
-                while found.contains(_symbol) {
-                    _symbol = rng.gen_range('A'..='Z');
+    /// Climb or descent with a rate, a crossing or passing restriction,
+    /// expedite or stop climb, the levels on either side of the transition
+    /// are named as altitudes or flight levels.
+    pub fn get_level_change(&mut self) -> String {
+        let (transition_altitude, transition_level) = self.transition();
+        // altitudes in steps of 1000 feet, flight levels in steps of 10
+        let mut levels: Vec<u32> = (2..=transition_altitude / 1000).map(|x| x * 1000).collect();
+        levels.extend((transition_level / 10..=41).map(|x| x * 1000));
+        let mut picked: Vec<u32> = levels.choose_multiple(&mut self.rng, 3).cloned().collect();
+        picked.sort();
+
+        let climb = self.rng.gen_bool(0.5);
+        let (target, passing) = if climb {
+            (picked[2], picked[1])
+        } else {
+            (picked[0], picked[1])
+        };
+        let target = self.level_text(target);
+        let passing = self.level_text(passing);
+        let (verb, noun, limit) = if climb {
+            ("climb", "climb", "above")
+        } else {
+            ("descend", "descent", "below")
+        };
+        let verb = match self.config.region {
+            Region::Faa => format!("{} and maintain", verb),
+            Region::Icao | Region::Cap413 => verb.to_string(),
+        };
+
+        match self.rng.gen_range(0..6) {
+            0 => format!("{} {}", verb, target),
+            1 => format!(
+                "{} {} at !{} feet per minute or {}",
+                verb,
+                target,
+                self.rng.gen_range(2..=6) * 500,
+                if self.rng.gen_bool(0.7) {
+                    "greater"
+                } else {
+                    "less"
                 }
-                found.push_str(_symbol.to_string().as_str());
+            ),
+            2 => {
+                let waypoint = self.get_waypoint();
+                format!(
+                    "{} {}, cross {} at or {} {}",
+                    verb, target, waypoint, limit, passing
+                )
             }
-            any_tmp =
-                // format!("{}{} ", any_tmp, alfabet_map[_symbol.to_string().as_str()]).to_string();
-                format!("{}{} ", any_tmp, _symbol);
-        } else if _symbol == '=' {
-            any_tmp = format!("{}{}", any_tmp, get_rw()).to_string();
-        } else {
-            any_tmp = format!("{}{}", any_tmp, _symbol).to_string();
+            3 => {
+                let heading = self.get_heading();
+                format!("{} {}, when passing {}, {}", verb, target, passing, heading)
+            }
+            4 => format!("{} {}, expedite until passing {}", verb, target, passing),
+            _ => format!("stop {} at {}", noun, passing),
         }
     }
 
-    any_tmp
-        .to_string()
-        .to_string()
-        .replace("  ", " ")
-        .trim()
-        .to_string()
-}
+    /// "altitude 4 thousand 6 hundred feet" for `feet` = 4600.
+    pub fn altitude_text(&self, feet: u32) -> String {
+        let alfabet_map: HashMap<&str, &str> = ALFABET.iter().cloned().collect();
+
+        let region = self.config.region;
+        let x = feet;
+        let thousands = x / 1000;
+        let hundreds = (x % 1000) / 100;
+        let mut alt_msg = String::new();
+        // FAA reads the thousands as a number, "ten thousand"
+        let number_mark = if region == Region::Faa { "!" } else { "" };
+        if thousands > 0 {
+            alt_msg = format!(
+                "{} {}{} {}",
+                alt_msg,
+                number_mark,
+                thousands,
+                alfabet_map.get("000").unwrap()
+            )
+            .to_string();
+        }
+        if hundreds > 0 {
+            alt_msg = format!(
+                "{} {} {}",
+                alt_msg,
+                hundreds,
+                alfabet_map.get("00").unwrap()
+            )
+            .to_string();
+        }
+        match region {
+            Region::Faa => format!("altitude{}", alt_msg),
+            Region::Icao | Region::Cap413 => format!("altitude{} feet", alt_msg),
+        }
+    }
 
-pub fn get_atis_message() -> String {
-    let mut rng = rand::thread_rng();
+    pub fn get_heading(&mut self) -> String {
+        let x = (self.rng.gen_range(10..=360) / 10) * 10;
 
-    let airports = [
-        "schiphol",
-        "dubai",
-        "new york",
-        "detroit",
-        "abu dhabi",
-        "warsaw",
-        "berlin",
-        "al bateen",
-    ];
+        match self.config.region {
+            Region::Faa => format!("fly heading {:03}", x),
+            Region::Icao | Region::Cap413 => format!("heading {:03}", x),
+        }
+    }
 
-    let mut atis_msg = "".to_string();
+    pub fn get_approach_type(&mut self) -> String {
+        let approaches = match self.config.region {
+            Region::Faa => ["!ILS", "!RNAV", "visual"],
+            Region::Icao | Region::Cap413 => ["!ILS", "!RNP", "visual"],
+        };
+
+        approaches[self.rng.gen_range(0..approaches.len())].to_string()
+    }
+
+    /// Approach speed in steps of 10 knots.
+    pub fn get_speed(&mut self) -> String {
+        format!("{} knots", self.rng.gen_range(16..=25) * 10)
+    }
+
+    /// Pressure setting in the units of the region: "qnh 1013 hectopascals",
+    /// the UK "qnh 1013" (hectopascals only below 1000) or "altimeter 2992".
+    pub fn get_qnh(&mut self) -> String {
+        match self.config.region {
+            Region::Icao => self.get_qnh_hpa(),
+            Region::Cap413 => {
+                let x = self.rng.gen_range(931..=1066);
+                if x < 1000 {
+                    format!("qnh {} hectopascals", x)
+                } else {
+                    format!("qnh {}", x)
+                }
+            }
+            Region::Faa => format!("altimeter {}", self.rng.gen_range(2750..=3150)),
+        }
+    }
+
+    pub fn get_qnh_hpa(&mut self) -> String {
+        format!("qnh {} hectopascals", self.rng.gen_range(931..=1066))
+    }
+
+    pub fn get_temperature(&mut self) -> String {
+        let mut temperature = self.rng.gen_range(-30..=50);
+        let mut dew_point = self.rng.gen_range((temperature - 15)..=temperature);
+        let mut minus_temp = "".to_string();
+        let mut minus_dp = "".to_string();
+        if temperature < 0 {
+            minus_temp = "minus".to_string();
+            temperature = 0 - temperature;
+        }
+        if dew_point < 0 {
+            minus_dp = "minus".to_string();
+            dew_point = 0 - dew_point;
+        }
 
-    let info_no = rng.gen_range('A'..='Z');
-    atis_msg.push_str(
-        format!(
-            "this is {} arrival information {}, ",
-            airports[rng.gen_range(0..=airports.len() - 1)],
-            info_no
-        )
-        .to_string()
-        .as_str(),
-    ); // atis_h1
-    atis_msg.push_str(format!("main landing {}, ", get_rw()).to_string().as_str()); // atis_h2
-    atis_msg.push_str(
-        format!("transition level {}, ", (rng.gen_range(50..=100) / 10) * 10)
-            .to_string()
-            .as_str(),
-    ); // atis_h3
-    atis_msg.push_str(format!("{}, ", get_wind()).to_string().as_str()); // atis_h4
-    atis_msg.push_str(
         format!(
-            "visibility !{} metres, ",
-            (rng.gen_range(100..=10000) / 100) * 100
+            "temperature {} {} dew point {} {}",
+            minus_temp, temperature, minus_dp, dew_point
         )
         .to_string()
-        .as_str(),
-    ); // atis_h5
-    atis_msg.push_str(
-        format!(
-            "clouds few !{}, scattered !{}, broken !{}, ",
-            (rng.gen_range(1000..=2500) / 100) * 100,
-            (rng.gen_range(2500..=3500) / 100) * 100,
-            (rng.gen_range(3500..=10000) / 100) * 100
-        )
+        .replace("  ", " ")
+        .trim()
         .to_string()
-        .as_str(),
-    ); // atis_h6
-    atis_msg.push_str(format!("{}, ", get_temperature()).to_string().as_str()); // atis_h7
-    atis_msg.push_str(format!("{}, ", get_qnh(false)).to_string().as_str()); // atis_h8
-    atis_msg.push_str("no significant change, ".to_string().as_str()); // atis_h9
-    atis_msg.push_str(
-        format!("end of information {}", info_no)
-            .to_string()
-            .as_str(),
-    ); // atis_h10
-
-    atis_msg.trim().to_string()
-}
+    }
 
-pub fn get_any_message() -> String {
-    let mut messages_map = MESSAGES.to_vec();
+    pub fn get_random_msg(&mut self) -> String {
+        self.render_category("clearance")
+    }
 
-    let config = clone_config();
+    pub fn get_atis_message(&mut self) -> String {
+        self.render_category("ATIS")
+    }
 
-    let mut tmp_msg = "".to_string();
-    tmp_msg.push_str(format!("{}, ", config.call_sign).as_str());
-    // shuffle messages
-    messages_map.shuffle(&mut rand::thread_rng());
+    pub fn get_any_message(&mut self) -> String {
+        let mut messages_map: Vec<(&str, &str)> = MESSAGES
+            .iter()
+            .filter(|(name, _)| {
+                self.config.mix.is_empty() || self.config.mix.iter().any(|m| m == name)
+            })
+            .cloned()
+            .collect();
+
+        let mut tmp_msg = "".to_string();
+        tmp_msg.push_str(format!("{}, ", self.config.call_sign).as_str());
+        // shuffle messages
+        messages_map.shuffle(&mut self.rng);
+
+        let items: Vec<String> = messages_map
+            .iter()
+            .map(|(_, text)| self.render(&Template::parse(text).unwrap()))
+            .collect();
+        tmp_msg.push_str(items.join(", ").as_str());
+
+        tmp_msg.trim().to_string()
+    }
 
-    let mut n = messages_map.len();
-    for (symbol, _) in messages_map {
-        let mut ss = "".to_string();
-        match symbol {
-            "=" => {
-                ss.push_str(get_rw().to_string().as_str());
-            }
-            "*" => {
-                ss.push_str(get_fl().to_string().as_str());
-            }
-            "!" => {
-                ss.push_str(get_qnh(true).to_string().as_str());
+    /// Value of one template slot, `None` for an unknown slot name.
+    pub fn get_slot(&mut self, name: &str) -> Option<String> {
+        let value = match name {
+            "callsign" => self.config.call_sign.clone(),
+            "flight_level" => self.get_fl(),
+            "altitude" => self.get_altitude(),
+            "heading" => self.get_heading(),
+            "squawk" => self.get_squawk(),
+            "runway" => self.get_rw(),
+            "station" => self.get_station(),
+            "frequency" => self.get_frequency_value(),
+            "contact" => self.get_frequency(),
+            "ground_contact" => self.get_frequency_for("ground"),
+            "qnh" => self.get_qnh(),
+            "qnh_hpa" => self.get_qnh_hpa(),
+            "wind" => self.get_wind(),
+            "wind_metar" => self.surface_wind().metar(),
+            "runway_winds" => wind::get_runway_winds(self),
+            "wind_shear" => wind::get_wind_shear(self),
+            "temperature" => self.get_temperature(),
+            "letter" => self.rng.gen_range('A'..='Z').to_string(),
+            "approach_type" => self.get_approach_type(),
+            "speed" => self.get_speed(),
+            "distance" => format!("{} miles", self.rng.gen_range(4..=12)),
+            "transponder" => transponder::get_transponder_instruction(self),
+            "readability" => self.rng.gen_range(1..=5).to_string(),
+            "level_change" => self.get_level_change(),
+            "direct" => waypoint::get_direct(self).0,
+            "hold" => crate::holding::get_hold(self).text,
+            "traffic" => crate::traffic::get_traffic(self).text,
+            "avoiding_action" => crate::traffic::get_avoiding_action(self).0.text,
+            "airport" => self.surface().airport.name.to_string(),
+            "destination" => {
+                let destinations = ["munich", "lyon", "vienna", "zurich", "milan"];
+                destinations[self.rng.gen_range(0..=destinations.len() - 1)].to_string()
             }
-            "$" => {
-                ss.push_str(get_altitude().to_string().as_str());
+            "sid" => format!(
+                "{}{}{}",
+                self.rng.gen_range('A'..='Z'),
+                self.rng.gen_range(1..=9),
+                self.rng.gen_range('A'..='Z')
+            ),
+            "taxiway" => {
+                let mut letters: Vec<char> = ('A'..='Z').collect();
+                letters.shuffle(&mut self.rng);
+                letters[..3]
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
             }
-            "@" => {
-                ss.push_str(get_frequency().to_string().as_str());
-            }
-            "^" => {
-                ss.push_str(get_heading().to_string().as_str());
-            }
-            "&" => {
-                ss.push_str(get_squawk().to_string().as_str());
-            }
-            "-" => {
-                ss.push_str(get_wind().to_string().as_str());
+            "transition_level" => self.transition().1.to_string(),
+            // "!" keeps the number from being read digit by digit
+            "visibility" => match self.config.region {
+                Region::Faa => format!("!{}", self.rng.gen_range(1..=10)), // statute miles
+                Region::Icao | Region::Cap413 => {
+                    format!("!{}", (self.rng.gen_range(100..=10000) / 100) * 100)
+                }
+            },
+            "cloud_few" => format!("!{}", (self.rng.gen_range(1000..=2500) / 100) * 100),
+            "cloud_scattered" => format!("!{}", (self.rng.gen_range(2500..=3500) / 100) * 100),
+            "cloud_broken" => format!("!{}", (self.rng.gen_range(3500..=10000) / 100) * 100),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Fills the slots of `template`, a slot used twice gets the same value
+    /// and a slot named after a phrase category gets one of its phrases.
+    pub fn render(&mut self, template: &Template) -> String {
+        self.render_nested(template, 0)
+    }
+
+    fn render_nested(&mut self, template: &Template, depth: u32) -> String {
+        let mut values: HashMap<&str, String> = HashMap::new();
+        let mut msg = "".to_string();
+
+        for part in &template.parts {
+            match part {
+                Part::Literal(text) => msg.push_str(text),
+                Part::Slot(name) => {
+                    if !values.contains_key(name.as_str()) {
+                        let value = match self.get_slot(name) {
+                            Some(value) => value,
+                            None if depth < 4 => self.render_category_nested(name, depth + 1),
+                            None => "".to_string(),
+                        };
+                        values.insert(name, value);
+                    }
+                    msg.push_str(&values[name.as_str()]);
+                }
             }
-            "+" => {
-                ss.push_str(get_random_msg().as_str());
+        }
+
+        msg.replace("  ", " ").trim().to_string()
+    }
+
+    /// Renders a random phrase of `category`, empty when there is none.
+    pub fn render_category(&mut self, category: &str) -> String {
+        self.render_category_nested(category, 0)
+    }
+
+    fn render_category_nested(&mut self, category: &str, depth: u32) -> String {
+        let template = match self.phrases.get(category) {
+            Some(templates) if !templates.is_empty() => {
+                templates[self.rng.gen_range(0..templates.len())].clone()
             }
-            _ => {
-                ss.push_str(symbol);
+            _ => return "".to_string(),
+        };
+        self.render_nested(&template, depth)
+    }
+
+    pub fn get_message(&mut self, type_of_msg: &str) -> String {
+        self.new_surface();
+        match type_of_msg {
+            "ANY" => self.get_any_message(),
+            "ATIS" => self.get_atis_message(),
+            _ if crate::pilot::is_pilot_call(type_of_msg) => {
+                crate::pilot::get_pilot_report(type_of_msg, self)
+                    .unwrap()
+                    .text
             }
-        }
-        tmp_msg.push_str(ss.as_str());
-        n -= 1;
-        if n != 0 {
-            tmp_msg.push_str(", ");
+            _ if self.phrases.get(type_of_msg).is_some() => self.render_category(type_of_msg),
+            _ => "Unknown type of the message!".to_string(),
         }
     }
 
-    tmp_msg.trim().to_string()
-}
-
-pub fn get_message(type_of_msg: &str) -> String {
-    match type_of_msg {
-        "ANY" => get_any_message(),
-        "ATIS" => get_atis_message(),
-        _ => "Unknown type of the message!".to_string(),
+    /// ANY, the pilot calls and every phrase category.
+    pub fn get_message_types(&self) -> Vec<String> {
+        let mut types = vec!["ANY".to_string()];
+        types.extend(crate::pilot::PILOT_CALLS.iter().map(|c| c.to_string()));
+        types.extend(self.phrases.categories().iter().map(|c| c.to_string()));
+        types
     }
 }
 
@@ -491,41 +742,32 @@ pub fn be_ready(mut val: u32) {
     io::stdout().flush().unwrap(); // Ensure the buffer is flushed
 }
 
-pub fn say(text: String, flag: bool, comma_pause: bool) -> Result<bool, String> {
+pub fn say(text: String, flag: bool, config: &mut ConfigElp) -> Result<bool, ElpError> {
     use std::thread;
     use std::time::Duration;
 
     // check voice, if defined then set voice and exit
-    match get_voices() {
-        Ok(platform_voices) => {
-            for (key, val) in &platform_voices {
-                if text.contains(key) {
-                    let mut config_lock = crate::elp_service::CONFIG.lock().unwrap();
-                    config_lock.voice = key.to_string();
-                    println!("{} ({})", config_lock.voice, val);
-                    drop(config_lock);
-                    return Ok(false);
-                }
-            }
+    for (key, val) in MAC_VOICES.iter() {
+        if text.contains(key) {
+            config.voice = key.to_string();
+            println!("{} ({})", config.voice, val);
+            return Ok(false);
         }
-        Err(error) => return Err(error),
     }
 
-    let config = clone_config();
-
     // delay if "Pause" found
     if text.contains("!Pause") {
         thread::sleep(Duration::from_secs(config.pause as u64));
         return Ok(false);
     }
 
-    if comma_pause {
+    if config.comma {
         for split_txt in text.split(',') {
             let tmp_say = split_txt.trim();
             if flag {
                 println!("{tmp_say}");
             }
-            say_service(tmp_say.to_string(), &config).unwrap();
+            say_service(tmp_say.to_string(), config)?;
             thread::sleep(Duration::from_micros(500));
         }
     } else {
@@ -533,65 +775,143 @@ pub fn say(text: String, flag: bool, comma_pause: bool) -> Result<bool, String>
         if flag {
             println!("{}", text);
         }
-        say_service(text.to_string(), &config).unwrap();
+        say_service(text.to_string(), config)?;
     }
 
-    drop(config);
     Ok(true)
 }
 
-fn say_service(text: String, config: &ConfigElp) -> Result<Output, std::io::Error> {
-    use std::io::Error;
-    use std::process::{Command, Output};
-
-    if cfg!(windows) {
-        println!("Unsupported platform - windows");
-        Ok(Output {
-            status: Default::default(),
-            stdout: vec![],
-            stderr: vec![],
-        })
-    } else if cfg!(target_os = "linux") {
-        println!("Unsupported platform - unix alike");
-        Ok(Output {
+fn say_service(text: String, config: &ConfigElp) -> Result<Output, ElpError> {
+    match spawn_say(&text, &config.voice, config)? {
+        Some(child) => Ok(child.wait_with_output()?),
+        None => Ok(Output {
             status: Default::default(),
             stdout: vec![],
             stderr: vec![],
+        }),
+    }
+}
+
+pub fn spawn_say(
+    text: &str,
+    voice: &str,
+    config: &ConfigElp,
+) -> Result<Option<std::process::Child>, ElpError> {
+    use std::process::Command;
+
+    if config.backend == "silent" {
+        return Ok(None);
+    }
+
+    let voices = get_voices()?;
+    let name = voices
+        .get(voice)
+        .ok_or_else(|| ElpError::UnknownVoice(voice.to_string()))?;
+    Command::new("say")
+        .arg(format!("--voice={}", name))
+        .arg(format!("--rate={}", config.rate))
+        .arg(text)
+        .spawn()
+        .map(Some)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                ElpError::MissingTtsEngine("'say' command not found".to_string())
+            }
+            _ => ElpError::Io(e),
         })
-    } else if cfg!(target_os = "macos") {
-        match get_voices() {
-            Ok(voices) => Command::new("say")
-                .arg(format!(
-                    "--voice={}",
-                    voices.get(config.voice.as_str()).unwrap()
-                ))
-                .arg(format!("--rate={}", config.rate))
-                .arg(text)
-                .output(),
-            Err(error) => Err(Error::new(std::io::ErrorKind::Other, error)),
+}
+
+/// Fails early when the configured backend can't speak on this platform.
+pub fn check_backend(config: &ConfigElp) -> Result<(), ElpError> {
+    if config.backend == "silent" {
+        return Ok(());
+    }
+    get_voices()?;
+    if !get_voice_names().contains(&config.voice.as_str()) {
+        return Err(ElpError::UnknownVoice(config.voice.clone()));
+    }
+    Ok(())
+}
+
+pub fn get_other_voice<R: Rng>(voice: &str, rng: &mut R) -> String {
+    let voices: HashMap<&str, &str> = MAC_VOICES.iter().cloned().collect();
+    let current = voices.get(voice).cloned().unwrap_or_default();
+    let mut others: Vec<&str> = voices
+        .iter()
+        .filter(|(_, val)| **val != current)
+        .map(|(key, _)| *key)
+        .collect();
+    others.sort();
+
+    match others.choose(rng) {
+        Some(key) => key.to_string(),
+        None => voice.to_string(),
+    }
+}
+
+pub fn print_voices() -> Result<(), ElpError> {
+    let voices = get_voices()?;
+    for key in get_voice_names() {
+        println!("{:<8} {}", key, voices[key]);
+    }
+    Ok(())
+}
+
+pub fn test_voices(voice: Option<&str>, config: &ConfigElp) -> Result<(), ElpError> {
+    let voices = get_voices()?;
+    let keys: Vec<&str> = match voice {
+        Some(voice) => vec![voice],
+        None => get_voice_names(),
+    };
+
+    for key in keys {
+        let text = format!("{}, radio check, how do you read", key);
+        println!("{} ({})", text, voices.get(key).unwrap_or(&"?"));
+        if let Some(mut child) = spawn_say(&text, key, config)? {
+            child.wait()?;
         }
-    } else {
-        Err(Error::new(
-            std::io::ErrorKind::Other,
-            format!("Unsupported platform {}", std::env::consts::OS,),
-        ))
     }
+    Ok(())
+}
+
+pub fn ask(prompt: &str) -> Result<String, ElpError> {
+    use std::io::{self, Write};
+
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_lowercase())
 }
 
 pub fn get_alfabet() -> HashMap<&'static str, &'static str> {
     ALFABET.iter().cloned().collect()
 }
 
-pub fn get_voices() -> Result<HashMap<&'static str, &'static str>, String> {
+/// Voice keys accepted by `--voice`, available on every platform.
+pub fn get_voice_names() -> Vec<&'static str> {
+    let mut names: Vec<&str> = MAC_VOICES.iter().map(|(key, _)| *key).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Voice keys mapped to the voices of the platform speech engine.
+pub fn get_voices() -> Result<HashMap<&'static str, &'static str>, ElpError> {
     if cfg!(target_os = "macos") {
         Ok(MAC_VOICES.iter().cloned().collect())
     } else {
-        Err(format!("Unsupported platform {}", std::env::consts::OS))
+        Err(ElpError::MissingTtsEngine(format!(
+            "unsupported platform {}",
+            std::env::consts::OS
+        )))
     }
 }
 
-pub fn prepare_message_for_say(msg: &str) -> String {
-    let alfabet_map = get_alfabet();
+pub fn prepare_message_for_say(msg: &str, region: Region) -> String {
+    let mut alfabet_map = get_alfabet();
+    alfabet_map.extend(region.words().iter().cloned());
 
     let mut skip = false;
     let mut tmp = "".to_string();
@@ -616,26 +936,39 @@ pub fn prepare_message_for_say(msg: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn test_get_rw() {
-        use crate::elp_service::get_rw;
+    use crate::elp_service::{ConfigElp, Generator};
+    use rand::rngs::ThreadRng;
 
-        let x = get_rw();
-        let mut y = x.replace("runway", "");
-        y = y.replace("left", "");
-        y = y.replace("right", "").trim().to_string();
-        let rw: i32 = y.parse().unwrap();
+    fn generator() -> Generator<ThreadRng> {
+        Generator::new(ConfigElp::default(), rand::thread_rng())
+    }
 
-        assert!(x.contains("runway"));
-        assert!(x.contains("left") || x.contains("right"));
-        assert!((0..=36).contains(&rw));
+    #[test]
+    fn test_get_rw() {
+        use crate::runway::angle;
+
+        let mut generator = generator();
+        for _ in 0..20 {
+            generator.new_surface();
+            let x = generator.get_rw();
+            let y = x
+                .replace("runway", "")
+                .replace("left", "")
+                .replace("centre", "")
+                .replace("right", "");
+            let rw: u32 = y.trim().parse().unwrap();
+            assert!(x.starts_with("runway "));
+            assert!((1..=36).contains(&rw));
+
+            if let Some(direction) = generator.surface_wind().direction {
+                assert!(angle(direction, rw * 10) <= 90);
+            }
+        }
     }
 
     #[test]
     fn test_get_fl() {
-        use crate::elp_service::get_fl;
-
-        let x = get_fl();
+        let x = generator().get_fl();
         let mut y = x.replace("flight", "");
         y = y.replace("level", "").trim().to_string();
         let fl: i32 = y.parse().unwrap();
@@ -648,46 +981,48 @@ mod tests {
 
     #[test]
     fn test_get_wind() {
-        use crate::elp_service::get_wind;
-
-        let x = get_wind();
-        let mut y = x.replace("wind", "");
-        y = y.replace("knots", "").trim().to_string();
-        let values: Vec<&str> = y.split("degree").collect();
-        let degree_str = values[0].trim().to_string();
-        let velocity_str = values[1].trim().to_string();
-
-        let degree: i32 = degree_str.parse().unwrap();
-        let velocity: i32 = velocity_str.parse().unwrap();
-
-        assert!(x.contains("wind"));
-        assert!(x.contains("degree"));
-        assert!(x.contains("knots"));
-        assert!((0..=360).contains(&degree));
-        assert!(degree % 10 == 0);
-        assert!((1..=50).contains(&velocity));
+        let mut generator = generator();
+        for _ in 0..50 {
+            generator.new_surface();
+            let x = generator.get_wind();
+            let wind = generator.surface_wind();
+
+            assert!(x.starts_with("wind "));
+            match wind.direction {
+                _ if wind.is_calm() => assert_eq!(x, "wind calm"),
+                Some(direction) => {
+                    assert!(x.starts_with(&format!(
+                        "wind {:03} degrees {} knots",
+                        direction, wind.speed
+                    )))
+                }
+                None => assert!(x.starts_with("wind variable ")),
+            }
+            assert!(!generator.get_message("wind").contains('{'));
+        }
     }
 
     #[test]
     fn test_get_squawk() {
-        use crate::elp_service::get_squawk;
-
-        let forbidden = [2000, 7000, 7500, 7600, 7700];
-
-        let x = get_squawk();
-        let y = x.replace("squawk", "").trim().to_string();
-        let sq: i32 = y.parse().unwrap();
-
-        assert!(x.contains("squawk"));
-        assert!((2000..=9999).contains(&sq));
-        assert!(!forbidden.contains(&sq));
+        use crate::transponder::is_octal;
+
+        let mut generator = generator();
+        for _ in 0..50 {
+            let x = generator.get_squawk();
+            let y = x.replace("squawk", "").trim().to_string();
+            let sq: u32 = y.parse().unwrap();
+
+            assert!(x.starts_with("squawk "));
+            assert_eq!(y.len(), 4);
+            assert!(is_octal(sq));
+            assert!(![2000, 7000, 7500, 7600, 7700].contains(&sq));
+        }
+        assert!(generator.get_message("transponder").contains("squawk"));
     }
 
     #[test]
     fn test_get_heading() {
-        use crate::elp_service::get_heading;
-
-        let x = get_heading();
+        let x = generator().get_heading();
         let y = x.replace("heading", "").trim().to_string();
         let h: i32 = y.parse().unwrap();
 
@@ -698,9 +1033,7 @@ mod tests {
 
     #[test]
     fn test_get_temperature() {
-        use crate::elp_service::get_temperature;
-
-        let x = get_temperature();
+        let x = generator().get_temperature();
         let mut y = x.replace("temperature", "").trim().to_string();
         y = y.replace("minus ", "-").trim().to_string();
         let values: Vec<&str> = y.split("dew point").collect();
@@ -718,9 +1051,7 @@ mod tests {
 
     #[test]
     fn test_get_altitude() {
-        use crate::elp_service::get_altitude;
-
-        let x = get_altitude();
+        let x = generator().get_altitude();
         assert!(x.contains("altitude"));
         assert!(x.contains("feet"));
 
@@ -749,35 +1080,183 @@ mod tests {
 
     #[test]
     fn test_get_qnh() {
-        use crate::elp_service::get_qnh;
-
-        let x = get_qnh(true);
-        let mut y = x.replace("qnh", "").trim().to_string();
-
-        if x.contains("hectopascals") {
-            y = y.replace("hectopascals", "").trim().to_string();
-            let qnh: i32 = y.parse().unwrap();
-            assert!((931..=1066).contains(&qnh));
-        } else if x.contains("inches") {
-            y = y.replace("inches", "").trim().to_string();
-            let qnh: f32 = y.parse().unwrap();
-            assert!((27.50..=31.50).contains(&qnh));
-        } else {
-            assert!(x.contains("somethingelse"));
+        let x = generator().get_qnh();
+        let y = x.replace("qnh", "").replace("hectopascals", "");
+        let qnh: i32 = y.trim().parse().unwrap();
+        assert!((931..=1066).contains(&qnh));
+        assert!(x.starts_with("qnh") && x.ends_with("hectopascals"));
+    }
+
+    #[test]
+    fn test_regions() {
+        use crate::elp_service::prepare_message_for_say;
+        use crate::region::Region;
+
+        let region_generator = |region| {
+            let config = ConfigElp {
+                region,
+                ..Default::default()
+            };
+            Generator::new(config, rand::thread_rng())
+        };
+
+        let mut faa = region_generator(Region::Faa);
+        let x = faa.get_qnh();
+        let altimeter: u32 = x.replace("altimeter ", "").parse().unwrap();
+        assert!((2750..=3150).contains(&altimeter));
+        assert!(
+            faa.get_fl()
+                .replace("flight level ", "")
+                .parse::<u32>()
+                .unwrap()
+                >= 180
+        );
+        assert!(!faa.get_wind().contains("degrees"));
+        assert!(!faa.get_altitude().contains("feet"));
+        assert_eq!(
+            prepare_message_for_say("altimeter 2992, !10 thousand", Region::Faa).trim(),
+            "altimeter two niner niner two, 10 thousand"
+        );
+        assert_eq!(
+            prepare_message_for_say("121.5", Region::Icao).trim(),
+            "wun too wun decimal five"
+        );
+
+        let mut uk = region_generator(Region::Cap413);
+        for _ in 0..20 {
+            let x = uk.get_qnh();
+            let qnh: u32 = x.split(' ').nth(1).unwrap().parse().unwrap();
+            assert_eq!(qnh < 1000, x.ends_with("hectopascals"));
         }
-        assert!(x.contains("qnh"));
     }
 
     #[test]
     fn test_get_frequency() {
-        use crate::elp_service::get_frequency;
-
-        let x = get_frequency();
+        let x = generator().get_frequency();
         assert!(x.contains("contact"));
 
         let frequency_str: String = x.chars().filter(|c| !c.is_alphabetic()).collect();
         let frequency: f32 = frequency_str.trim().parse().unwrap();
 
-        assert!((117.975..=137.0).contains(&frequency))
+        assert!((117.975..=137.0).contains(&frequency));
+
+        // the channel is one of the station's
+        let mut generator = generator();
+        for _ in 0..50 {
+            let x = generator.get_slot("contact").unwrap();
+            let words: Vec<&str> = x.split(' ').collect();
+            let (_, range) = crate::frequency::STATION_TYPES
+                .iter()
+                .find(|(name, _)| *name == words[2])
+                .unwrap();
+            let (mhz, khz) = words[3].split_once('.').unwrap();
+            let name: u32 = format!("{}{:0<3}", mhz, khz).parse().unwrap();
+            assert!(range.contains(&name), "{}", x);
+        }
+    }
+
+    #[test]
+    fn test_generator_seeded() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut a = Generator::new(ConfigElp::default(), StdRng::seed_from_u64(7));
+        let mut b = Generator::new(ConfigElp::default(), StdRng::seed_from_u64(7));
+
+        assert_eq!(a.get_message("ANY"), b.get_message("ANY"));
+        assert_eq!(a.get_message("ATIS"), b.get_message("ATIS"));
+    }
+
+    #[test]
+    fn test_render() {
+        use crate::template::{PhraseBook, Template};
+
+        let mut generator = generator();
+        generator.phrases_mut().merge(
+            PhraseBook::parse("[phrases]\ntaxi = [\"{callsign}, taxi to holding point {runway}\"]")
+                .unwrap(),
+        );
+
+        let x = generator.render(&Template::parse("{runway}, cleared to land {runway}").unwrap());
+        let values: Vec<&str> = x.split(", cleared to land ").collect();
+        assert_eq!(values[0], values[1]);
+
+        let x = generator.get_message("taxi");
+        assert!(x.starts_with("A6KIB, taxi to holding point runway "));
+
+        let x = generator.get_message("ATIS");
+        assert!(x.starts_with("this is "));
+        assert!(!x.contains('{'));
+        assert!(generator.get_message_types().contains(&"taxi".to_string()));
+    }
+
+    #[test]
+    fn test_level_change() {
+        let config = ConfigElp {
+            transition_altitude: Some(6500),
+            ..Default::default()
+        };
+        let mut generator = Generator::new(config, rand::thread_rng());
+        assert_eq!(generator.transition(), (6500, 80));
+        assert_eq!(generator.level_text(6000), "altitude 6 thousand feet");
+        assert_eq!(generator.level_text(8000), "flight level 80");
+        assert_eq!(
+            crate::elp_service::tests::generator().transition(),
+            (5000, 60)
+        );
+
+        // the ATIS announces the transition level the instructions use
+        let x = generator.get_message("ATIS");
+        let level: u32 = x
+            .split("transition level ")
+            .nth(1)
+            .unwrap()
+            .split(',')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(level, 80);
+        assert!(level * 100 > 6500);
+
+        for _ in 0..50 {
+            let x = generator.get_level_change();
+            assert!(["climb ", "descend ", "stop "]
+                .iter()
+                .any(|v| x.starts_with(v)));
+            for level in x.split("flight level ").skip(1) {
+                let level: u32 = level.split([' ', ',']).next().unwrap().parse().unwrap();
+                assert!((80..=410).contains(&level));
+            }
+        }
+    }
+
+    #[test]
+    fn test_approach_and_landing() {
+        use crate::elp_service::prepare_message_for_say;
+        use crate::region::Region;
+
+        for region in [Region::Icao, Region::Faa, Region::Cap413] {
+            let config = ConfigElp {
+                region,
+                ..Default::default()
+            };
+            let mut generator = Generator::new(config, rand::thread_rng());
+            for _ in 0..10 {
+                for msg_type in ["approach", "landing"] {
+                    let x = generator.get_message(msg_type);
+                    assert!(x.starts_with("A6KIB, "));
+                    assert!(!x.contains('{'));
+                    assert!(!prepare_message_for_say(&x, region).contains("india lima"));
+                }
+            }
+        }
+
+        let speed: u32 = generator()
+            .get_speed()
+            .replace(" knots", "")
+            .parse()
+            .unwrap();
+        assert!((160..=250).contains(&speed) && speed.is_multiple_of(10));
     }
 }
@@ -0,0 +1,89 @@
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum ElpError {
+    /// A command line option or config value that can't be used.
+    InvalidArgument(String),
+    /// No text-to-speech engine on this platform, or it failed to start.
+    MissingTtsEngine(String),
+    UnknownVoice(String),
+    Io(std::io::Error),
+    /// A config, stats or phrase file that can't be read or parsed.
+    DataFile {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl ElpError {
+    /// Process exit code, 2 matches the code clap uses for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ElpError::InvalidArgument(_) => 2,
+            ElpError::MissingTtsEngine(_) => 3,
+            ElpError::UnknownVoice(_) => 4,
+            ElpError::DataFile { .. } => 5,
+            ElpError::Io(_) => 6,
+        }
+    }
+}
+
+impl fmt::Display for ElpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElpError::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            ElpError::MissingTtsEngine(message) => write!(
+                f,
+                "text-to-speech engine not available: {} (use --backend silent to train without audio)",
+                message
+            ),
+            ElpError::UnknownVoice(voice) => write!(f, "unknown voice '{}'", voice),
+            ElpError::Io(error) => write!(f, "I/O error: {}", error),
+            ElpError::DataFile { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ElpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ElpError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ElpError {
+    fn from(error: std::io::Error) -> ElpError {
+        ElpError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_exit_codes() {
+        use crate::error::ElpError;
+        use std::path::PathBuf;
+
+        let errors = [
+            ElpError::InvalidArgument("count".to_string()),
+            ElpError::MissingTtsEngine("linux".to_string()),
+            ElpError::UnknownVoice("ATC9".to_string()),
+            ElpError::DataFile {
+                path: PathBuf::from("config.toml"),
+                message: "expected `=`".to_string(),
+            },
+            ElpError::Io(std::io::Error::other("broken pipe")),
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(|e| e.exit_code()).collect();
+        codes.dedup();
+
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| *code > 1));
+        assert_eq!(errors[2].to_string(), "unknown voice 'ATC9'");
+    }
+}
@@ -0,0 +1,86 @@
+use crate::elp_service::{prepare_message_for_say, Generator};
+use crate::region::Region;
+use rand::Rng;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const FORMATS: [&str; 2] = ["text", "csv"];
+
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+pub fn format_messages(
+    messages: &[String],
+    msg_type: &str,
+    format: &str,
+    region: Region,
+) -> String {
+    let mut out = String::new();
+
+    if format == "csv" {
+        out.push_str("index,type,text,spoken\n");
+    }
+    for (n, msg) in messages.iter().enumerate() {
+        let text = msg.replace('!', "");
+        if format == "csv" {
+            out.push_str(
+                format!(
+                    "{},{},{},{}\n",
+                    n + 1,
+                    msg_type,
+                    csv_field(&text),
+                    csv_field(prepare_message_for_say(msg, region).trim())
+                )
+                .as_str(),
+            );
+        } else {
+            out.push_str(format!("{}\n", text).as_str());
+        }
+    }
+
+    out
+}
+
+pub fn export<R: Rng>(
+    generator: &mut Generator<R>,
+    format: &str,
+    output: Option<&PathBuf>,
+) -> Result<(), std::io::Error> {
+    let config = generator.config().clone();
+    let messages: Vec<String> = (0..config.num_msg)
+        .map(|_| generator.get_message(config.msg_type.as_str()))
+        .collect();
+    let out = format_messages(&messages, &config.msg_type, format, config.region);
+
+    match output {
+        Some(path) => std::fs::write(path, out),
+        None => std::io::stdout().write_all(out.as_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_format_messages() {
+        use crate::export::format_messages;
+        use crate::region::Region;
+
+        let messages = vec![
+            "A6KIA, heading 090".to_string(),
+            "visibility !5000 metres".to_string(),
+        ];
+
+        assert_eq!(
+            format_messages(&messages, "ANY", "text", Region::Icao),
+            "A6KIA, heading 090\nvisibility 5000 metres\n"
+        );
+
+        let csv = format_messages(&messages, "ANY", "csv", Region::Icao);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "index,type,text,spoken");
+        assert!(lines[1].starts_with("1,ANY,\"A6KIA, heading 090\",\""));
+        assert!(lines[1].contains("zero niner zero"));
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::region::Region;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+/// Station types and the channel names they use, in kHz.
+pub const STATION_TYPES: [(&str, RangeInclusive<u32>); 5] = [
+    ("ground", 121600..=121975),
+    ("tower", 118000..=121400),
+    ("approach", 119000..=127975),
+    ("departure", 124000..=136975),
+    ("information", 124000..=136975),
+];
+
+const CITIES: [&str; 5] = ["munich", "berlin", "dubai", "fujairah", "vienna"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spacing {
+    Khz25,
+    Khz8_33,
+}
+
+/// A VHF channel by its name in kHz, 132005 for "132.005". A 25 kHz block
+/// starting at 132.000 holds the 25 kHz channel 132.000 and the 8.33 kHz
+/// channels 132.005, 132.010 and 132.015, names ending in 20, 45, 70 and
+/// 95 are not used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Channel {
+    pub name: u32,
+}
+
+impl Channel {
+    /// `None` for a name outside 118.000 to 136.990 or not in the channel plan.
+    pub fn from_name(name: u32) -> Option<Channel> {
+        let offset = name % 25;
+        if (118000..=136990).contains(&name) && offset.is_multiple_of(5) && offset != 20 {
+            Some(Channel { name })
+        } else {
+            None
+        }
+    }
+
+    pub fn spacing(self) -> Spacing {
+        if self.name.is_multiple_of(25) {
+            Spacing::Khz25
+        } else {
+            Spacing::Khz8_33
+        }
+    }
+
+    /// Frequency the radio is tuned to, in Hz: 132.010 is 132.00833 MHz.
+    pub fn frequency_hz(self) -> u32 {
+        let block = self.name - self.name % 25;
+        let slot = match self.spacing() {
+            Spacing::Khz25 => 0,
+            Spacing::Khz8_33 => self.name % 25 / 5 - 1,
+        };
+        block * 1000 + (slot * 25000 + 1) / 3
+    }
+
+    /// Channel as said on the radio. ICAO uses all six digits unless a
+    /// 25 kHz channel ends in 00 ("118.1"), 8.33 kHz channels always have
+    /// six. The FAA drops the trailing zeros.
+    pub fn text(self, region: Region) -> String {
+        let six = format!("{}.{:03}", self.name / 1000, self.name % 1000);
+        match region {
+            Region::Faa => {
+                let trimmed = six.trim_end_matches('0');
+                if trimmed.ends_with('.') {
+                    format!("{}0", trimmed)
+                } else {
+                    trimmed.to_string()
+                }
+            }
+            Region::Icao | Region::Cap413 => {
+                if self.spacing() == Spacing::Khz25 && self.name.is_multiple_of(100) {
+                    six[..six.len() - 2].to_string()
+                } else {
+                    six
+                }
+            }
+        }
+    }
+}
+
+/// Random channel for `station_type` (one of STATION_TYPES, anything else
+/// picks from the whole band). The FAA has no 8.33 kHz channels.
+pub fn random_channel<R: Rng>(station_type: &str, region: Region, rng: &mut R) -> Channel {
+    let range = STATION_TYPES
+        .iter()
+        .find(|(name, _)| *name == station_type)
+        .map(|(_, range)| range.clone())
+        .unwrap_or(118000..=136975);
+    loop {
+        let block = rng.gen_range(range.clone()) / 25 * 25;
+        let name = match region {
+            Region::Faa => block,
+            Region::Icao | Region::Cap413 => block + rng.gen_range(0..4) * 5,
+        };
+        if let Some(channel) = Channel::from_name(name) {
+            if range.contains(&name) && name != 121500 {
+                return channel;
+            }
+        }
+    }
+}
+
+/// Station of the type with one of its channels: ("munich tower", 118.705).
+pub fn station_channel<R: Rng>(
+    station_type: &str,
+    region: Region,
+    rng: &mut R,
+) -> (String, Channel) {
+    let station = format!("{} {}", CITIES.choose(rng).unwrap(), station_type);
+    (station, random_channel(station_type, region, rng))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_channels() {
+        use crate::frequency::{random_channel, Channel, Spacing, STATION_TYPES};
+        use crate::region::Region;
+
+        let channel = Channel::from_name(132010).unwrap();
+        assert_eq!(channel.spacing(), Spacing::Khz8_33);
+        assert_eq!(channel.frequency_hz(), 132_008_333);
+        assert_eq!(
+            Channel::from_name(132005).unwrap().frequency_hz(),
+            132_000_000
+        );
+        assert_eq!(
+            Channel::from_name(132040).unwrap().frequency_hz(),
+            132_041_667
+        );
+        assert_eq!(Channel::from_name(132020), None);
+        assert_eq!(Channel::from_name(117975), None);
+
+        let channel = Channel::from_name(118100).unwrap();
+        assert_eq!(channel.text(Region::Icao), "118.1");
+        assert_eq!(channel.text(Region::Faa), "118.1");
+        let channel = Channel::from_name(118150).unwrap();
+        assert_eq!(channel.text(Region::Icao), "118.150");
+        assert_eq!(channel.text(Region::Faa), "118.15");
+        assert_eq!(
+            Channel::from_name(132005).unwrap().text(Region::Icao),
+            "132.005"
+        );
+        assert_eq!(
+            Channel::from_name(121000).unwrap().text(Region::Faa),
+            "121.0"
+        );
+
+        let mut rng = rand::thread_rng();
+        for (station_type, range) in STATION_TYPES {
+            for _ in 0..50 {
+                let channel = random_channel(station_type, Region::Icao, &mut rng);
+                assert!(range.contains(&channel.name));
+                let channel = random_channel(station_type, Region::Faa, &mut rng);
+                assert_eq!(channel.spacing(), Spacing::Khz25);
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::region::Region;
+use rand::Rng;
+
+pub const ENTRIES: [&str; 3] = ["direct", "parallel", "teardrop"];
+
+/// A hold clearance, `text` is the spoken instruction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hold {
+    pub fix: String,
+    pub inbound: u32,
+    pub right_turns: bool,
+    pub text: String,
+}
+
+pub fn get_hold<R: Rng>(generator: &mut Generator<R>) -> Hold {
+    let fix = generator.get_waypoint();
+    let level = generator.get_fl();
+    let rng = generator.rng();
+    let inbound = rng.gen_range(1..=72) * 5;
+    let right_turns = rng.gen_range(0..4) > 0;
+    let minutes = rng.gen_range(0..24 * 60);
+    let time = format!("{:02}{:02}", minutes / 60, minutes % 60);
+    let leg = match rng.gen_range(0..3) {
+        0 => "1 minute",
+        1 => "1.5 minutes",
+        _ => "5 miles",
+    };
+    let expect = if rng.gen_range(0..2) == 0 {
+        "approach"
+    } else {
+        "further"
+    };
+    let as_published = rng.gen_range(0..3) == 0;
+
+    let text =
+        match generator.config().region {
+            // a published hold replaces course, turns and leg length
+            Region::Faa if as_published => format!(
+                "hold at {} as published, maintain {}, expect further clearance {}",
+                fix, level, time
+            ),
+            Region::Faa => format!(
+            "hold at {}, inbound course {:03}, {}{} legs, maintain {}, expect further clearance {}",
+            fix,
+            inbound,
+            // right turns are standard and not stated
+            if right_turns { "" } else { "left turns, " },
+            leg.replace("minutes", "minute").replace("miles", "mile"),
+            level,
+            time
+        ),
+            Region::Icao | Region::Cap413 => format!(
+            "hold at {} {}, inbound track {:03}, {} hand pattern, {} {}, expect {} clearance at {}",
+            fix,
+            level,
+            inbound,
+            if right_turns { "right" } else { "left" },
+            if leg.ends_with("miles") { "leg length" } else { "outbound time" },
+            leg,
+            expect,
+            time
+        ),
+        };
+
+    Hold {
+        fix,
+        inbound,
+        right_turns,
+        text,
+    }
+}
+
+/// Entry into the hold for an aircraft reaching the fix on `heading`:
+/// with right turns the teardrop sector is 110 to 180 degrees right of the
+/// inbound track, the parallel sector 180 to 290, the rest is direct.
+/// Left turns mirror the sectors.
+pub fn holding_entry(heading: u32, inbound: u32, right_turns: bool) -> &'static str {
+    let mut relative = (heading % 360 + 360 - inbound % 360) % 360;
+    if !right_turns {
+        relative = (360 - relative) % 360;
+    }
+
+    match relative {
+        111..=179 => "teardrop",
+        180..=289 => "parallel",
+        _ => "direct",
+    }
+}
+
+/// The racetrack with the inbound track pointing up.
+pub fn diagram(hold: &Hold) -> String {
+    let fix = hold.fix.replace('!', "");
+    let lines = if hold.right_turns {
+        [
+            format!("  {}", fix),
+            "    X------.".to_string(),
+            "    ^      |".to_string(),
+            "    |      |".to_string(),
+            "    |      v".to_string(),
+            "    '------'".to_string(),
+        ]
+    } else {
+        [
+            format!("         {}", fix),
+            "  .------X".to_string(),
+            "  |      ^".to_string(),
+            "  |      |".to_string(),
+            "  v      |".to_string(),
+            "  '------'".to_string(),
+        ]
+    };
+
+    format!(
+        "{}\n  inbound {:03}, {} turns\n",
+        lines.join("\n"),
+        hold.inbound,
+        if hold.right_turns { "right" } else { "left" }
+    )
+}
+
+/// The hold clearance is spoken and drawn, the trainee names the entry for
+/// a random heading. Within 5 degrees of a sector boundary both entries count.
+pub fn run_holding_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let hold = get_hold(generator);
+        let heading = generator.rng().gen_range(1..=72) * 5;
+        let msg = format!("{}, {}", config.call_sign, hold.text);
+        println!("{}", msg.replace('!', ""));
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+        print!("{}", diagram(&hold));
+        println!("  your heading {:03}", heading);
+
+        let answer = ask(&format!(
+            "[{}/{}] Entry ({}): ",
+            n,
+            config.num_msg,
+            ENTRIES.join(", ")
+        ))?;
+        let entry = holding_entry(heading, hold.inbound, hold.right_turns);
+        let accepted = [heading + 355, heading, heading + 5]
+            .iter()
+            .any(|h| holding_entry(*h, hold.inbound, hold.right_turns) == answer);
+
+        if accepted {
+            println!("Correct.");
+            score += 1;
+        } else {
+            println!("Incorrect, {} entry.", entry);
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_holding_entry() {
+        use crate::holding::holding_entry;
+
+        assert_eq!(holding_entry(45, 360, true), "direct");
+        assert_eq!(holding_entry(135, 360, true), "teardrop");
+        assert_eq!(holding_entry(225, 360, true), "parallel");
+        assert_eq!(holding_entry(315, 360, true), "direct");
+
+        assert_eq!(holding_entry(225, 360, false), "teardrop");
+        assert_eq!(holding_entry(135, 360, false), "parallel");
+
+        // inbound 090, right turns: teardrop from 200 to 270
+        assert_eq!(holding_entry(240, 90, true), "teardrop");
+        assert_eq!(holding_entry(300, 90, true), "parallel");
+        assert_eq!(holding_entry(20, 90, true), "direct");
+    }
+
+    #[test]
+    fn test_get_hold() {
+        use crate::elp_service::{ConfigElp, Generator};
+        use crate::holding::{diagram, get_hold};
+
+        let mut generator = Generator::new(ConfigElp::default(), rand::thread_rng());
+        let hold = get_hold(&mut generator);
+        assert!(hold.text.starts_with(&format!("hold at {}", hold.fix)));
+        assert!(hold.text.contains(&format!("{:03}", hold.inbound)));
+        assert!(diagram(&hold).contains(&hold.fix.replace('!', "")));
+        assert!(!generator.get_message("holding").contains('{'));
+
+        let config = ConfigElp {
+            region: crate::region::Region::Faa,
+            ..Default::default()
+        };
+        let mut generator = Generator::new(config, rand::thread_rng());
+        for _ in 0..50 {
+            let hold = get_hold(&mut generator);
+            assert_ne!(
+                hold.text.contains("as published"),
+                hold.text.contains("inbound course")
+            );
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! ELP training tool for pilots: generators of ATC messages and the
+//! exercises built on them. The `elp` binary is a thin command line
+//! wrapper over this crate.
+//!
+//! ```no_run
+//! use elp::elp_service::{ConfigElp, Generator};
+//!
+//! let mut generator = Generator::new(ConfigElp::default(), rand::thread_rng());
+//! println!("{}", generator.get_message("ANY"));
+//! ```
+
+pub mod blocked;
+pub mod config_file;
+pub mod controller;
+pub mod crosswind;
+pub mod dictation;
+pub mod elp_service;
+pub mod error;
+pub mod export;
+pub mod frequency;
+pub mod holding;
+pub mod packs;
+pub mod parser;
+pub mod phonetic;
+pub mod pilot;
+pub mod radio_check;
+pub mod readback;
+pub mod region;
+pub mod runway;
+pub mod scenario;
+pub mod stats;
+pub mod template;
+pub mod traffic;
+pub mod transponder;
+pub mod waypoint;
+pub mod wind;
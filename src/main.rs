@@ -1,27 +1,129 @@
-mod elp_service;
 mod parse_args;
 
-fn main() {
-    use crate::elp_service::{clone_config, get_message, prepare_message_for_say, say};
-
-    parse_args::parse_args();
-
-    let config = clone_config();
-    println!("{:?}", config);
+use crate::parse_args::Action;
+use elp::config_file::to_toml;
+use elp::elp_service::{be_ready, check_backend, prepare_message_for_say, say, Generator};
+use elp::error::ElpError;
+use elp::{
+    blocked, controller, crosswind, dictation, elp_service, export, holding, packs, phonetic,
+    pilot, radio_check, readback, scenario, stats, traffic, transponder, waypoint,
+};
+use rand::Rng;
 
+fn run_drill<R: Rng>(generator: &mut Generator<R>) -> Result<(), ElpError> {
+    let mut config = generator.config().clone();
     let mut num_msg = config.num_msg;
+    if pilot::is_pilot_call(&config.msg_type) {
+        config.voice = "Pilot".to_string();
+    }
 
     while num_msg > 0 {
-        elp_service::be_ready(config.pause);
+        be_ready(config.pause);
 
-        let msg = get_message(config.msg_type.as_str());
+        let msg = generator.get_message(config.msg_type.as_str());
 
-        let msg_say = prepare_message_for_say(&msg);
+        let msg_say = prepare_message_for_say(&msg, config.region);
 
-        // say("Pilot".to_string(), false, config.comma).unwrap();
+        // say("Pilot".to_string(), false, &mut config)?;
         println!("{}", msg.replace('!', ""));
-        say(msg_say, false, config.comma).unwrap();
+        say(msg_say, false, &mut config)?;
 
         num_msg -= 1;
     }
+    Ok(())
+}
+
+fn run() -> Result<(), ElpError> {
+    let (action, config) = parse_args::parse_args()?;
+
+    match &action {
+        Action::PacksList => return packs::print_packs(),
+        Action::PacksValidate(dirs) => return packs::validate_packs(dirs),
+        Action::PacksInstall(dir) => {
+            let pack = packs::install(dir)?;
+            println!(
+                "Installed {} {} to {}",
+                pack.info.name,
+                pack.info.version,
+                pack.dir.display()
+            );
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let mut generator = Generator::new(config, rand::thread_rng());
+    generator.load_phrases()?;
+
+    match action {
+        Action::Drill => {
+            check_backend(generator.config())?;
+            println!("{:?}", generator.config());
+            run_drill(&mut generator)
+        }
+        Action::Quiz(kind) => {
+            check_backend(generator.config())?;
+            let (correct, total) = match kind.as_str() {
+                "holding" => holding::run_holding_session(&mut generator)?,
+                "traffic" => traffic::run_traffic_session(&mut generator)?,
+                "readability" => radio_check::run_radio_check_session(&mut generator)?,
+                "squawk" => transponder::run_squawk_session(&mut generator)?,
+                "crosswind" => crosswind::run_crosswind_session(&mut generator)?,
+                "waypoint" => waypoint::run_waypoint_session(&mut generator)?,
+                _ => blocked::run_blocked_session(&mut generator)?,
+            };
+            Ok(stats::record(&format!("quiz-{}", kind), correct, total)?)
+        }
+        Action::Readback => {
+            check_backend(generator.config())?;
+            let (correct, total) = readback::run_readback_session(&mut generator)?;
+            Ok(stats::record("readback", correct, total)?)
+        }
+        Action::Dictation => {
+            check_backend(generator.config())?;
+            let (correct, total) = dictation::run_dictation_session(&mut generator)?;
+            Ok(stats::record("dictation", correct, total)?)
+        }
+        Action::Controller(kinds) => {
+            check_backend(generator.config())?;
+            let (correct, total) = controller::run_controller_session(&mut generator, &kinds)?;
+            Ok(stats::record("controller", correct, total)?)
+        }
+        Action::Speak(kinds) => {
+            check_backend(generator.config())?;
+            let (correct, total) = pilot::run_speaking_session(&mut generator, &kinds)?;
+            Ok(stats::record("speak", correct, total)?)
+        }
+        Action::Phonetic(mode) => {
+            check_backend(generator.config())?;
+            let (correct, total) = phonetic::run_phonetic_session(&mut generator, &mode)?;
+            Ok(stats::record(
+                &format!("phonetic-{}", mode),
+                correct,
+                total,
+            )?)
+        }
+        Action::Scenario(name) => {
+            check_backend(generator.config())?;
+            scenario::run_scenario(&name, &mut generator)
+        }
+        Action::Export { format, output } => {
+            Ok(export::export(&mut generator, &format, output.as_ref())?)
+        }
+        Action::VoicesList => elp_service::print_voices(),
+        Action::VoicesTest(voice) => elp_service::test_voices(voice.as_deref(), generator.config()),
+        Action::Stats => Ok(stats::print_stats()?),
+        Action::PacksList | Action::PacksValidate(_) | Action::PacksInstall(_) => Ok(()),
+        Action::ShowConfig => {
+            print!("{}", to_toml(generator.config()));
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("elp: {}", error);
+        std::process::exit(error.exit_code());
+    }
 }
@@ -0,0 +1,307 @@
+use crate::config_file::data_dir;
+use crate::elp_service::{ConfigElp, Generator};
+use crate::error::ElpError;
+use crate::region::{Region, REGIONS};
+use crate::template::PhraseBook;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "pack.toml";
+
+/// The `[pack]` table of `pack.toml`:
+///
+/// ```toml
+/// [pack]
+/// name = "dubai-ground"
+/// version = "1.0.0"
+/// region = "ICAO"
+/// tags = ["beginner", "ground"]
+/// description = "Taxi and start-up phrases for OMDB"
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackInfo {
+    pub name: String,
+    pub version: String,
+    pub region: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    pack: PackInfo,
+}
+
+/// A directory holding `pack.toml` and any number of phrase files (`*.toml`).
+#[derive(Clone, Debug)]
+pub struct Pack {
+    pub info: PackInfo,
+    pub dir: PathBuf,
+    pub phrases: PhraseBook,
+}
+
+impl Pack {
+    pub fn load(dir: &Path) -> Result<Pack, ElpError> {
+        let manifest_path = dir.join(MANIFEST);
+        let data_error = |message: String| ElpError::DataFile {
+            path: manifest_path.clone(),
+            message,
+        };
+        let text = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| data_error(format!("can't read pack manifest: {}", e)))?;
+        let manifest: Manifest = toml::from_str(&text)
+            .map_err(|e| data_error(format!("invalid pack manifest: {}", e)))?;
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "toml")
+                    && path.file_name().is_some_and(|name| name != MANIFEST)
+            })
+            .collect();
+        files.sort();
+
+        let mut phrases = PhraseBook::default();
+        for file in files {
+            phrases.merge(PhraseBook::load(&file)?);
+        }
+
+        Ok(Pack {
+            info: manifest.pack,
+            dir: dir.to_path_buf(),
+            phrases,
+        })
+    }
+
+    /// Problems found in the pack, empty when it is usable.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let info = &self.info;
+
+        if info.name.is_empty()
+            || !info
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            problems.push(format!("invalid pack name '{}'", info.name));
+        }
+        let version: Vec<&str> = info.version.split('.').collect();
+        if version.len() != 3 || version.iter().any(|v| v.parse::<u32>().is_err()) {
+            problems.push(format!(
+                "version '{}' is not MAJOR.MINOR.PATCH",
+                info.version
+            ));
+        }
+        if !REGIONS.contains(&info.region.as_str()) {
+            problems.push(format!(
+                "unknown region '{}', expected one of {}",
+                info.region,
+                REGIONS.join(", ")
+            ));
+        }
+        if self.phrases.categories().is_empty() {
+            problems.push("no phrases".to_string());
+        }
+
+        // pack templates may use the built-in categories as slots
+        let region = Region::parse(&info.region).unwrap_or_default();
+        let mut book = PhraseBook::builtin_for(region);
+        book.merge(self.phrases.clone());
+        for slot in self.phrases.unknown_slots() {
+            if book.unknown_slots().contains(&slot) {
+                problems.push(format!("unknown slot in {}", slot));
+            }
+        }
+        for category in self.phrases.categories() {
+            if book.is_recursive(category) {
+                problems.push(format!("category '{}' refers to itself", category));
+            }
+        }
+
+        if problems.is_empty() {
+            let config = ConfigElp {
+                region,
+                ..Default::default()
+            };
+            let mut generator = Generator::new(config, rand::thread_rng());
+            *generator.phrases_mut() = book;
+            for category in self.phrases.categories() {
+                for template in self.phrases.get(category).unwrap() {
+                    let msg = generator.render(template);
+                    if msg.is_empty() || msg.contains(",,") {
+                        problems.push(format!(
+                            "{}: \"{}\" renders as \"{}\"",
+                            category, template.text, msg
+                        ));
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// `$XDG_DATA_HOME/elp/packs`, one sub-directory per installed pack.
+pub fn packs_dir() -> Option<PathBuf> {
+    Some(data_dir()?.join("packs"))
+}
+
+pub fn installed_packs() -> Result<Vec<Result<Pack, ElpError>>, ElpError> {
+    let dir = match packs_dir() {
+        Some(dir) if dir.exists() => dir,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    Ok(dirs.iter().map(|dir| Pack::load(dir)).collect())
+}
+
+pub fn find_pack(name: &str) -> Result<Pack, ElpError> {
+    for pack in installed_packs()?.into_iter().flatten() {
+        if pack.info.name == name {
+            return Ok(pack);
+        }
+    }
+    Err(ElpError::InvalidArgument(format!(
+        "phrase pack '{}' is not installed, see `elp packs list`",
+        name
+    )))
+}
+
+/// Validates the pack in `dir` and copies it to the packs directory.
+pub fn install(dir: &Path) -> Result<Pack, ElpError> {
+    let pack = Pack::load(dir)?;
+    let problems = pack.validate();
+    if !problems.is_empty() {
+        return Err(ElpError::DataFile {
+            path: dir.to_path_buf(),
+            message: problems.join("; "),
+        });
+    }
+
+    let target = packs_dir()
+        .ok_or_else(|| ElpError::InvalidArgument("can't find the data directory".to_string()))?
+        .join(&pack.info.name);
+    std::fs::create_dir_all(&target)?;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            std::fs::copy(&path, target.join(path.file_name().unwrap()))?;
+        }
+    }
+
+    Pack::load(&target)
+}
+
+pub fn print_packs() -> Result<(), ElpError> {
+    let packs = installed_packs()?;
+    if packs.is_empty() {
+        println!("No phrase packs installed.");
+        return Ok(());
+    }
+
+    for pack in packs {
+        match pack {
+            Ok(pack) => println!(
+                "{:<20} {:<8} {:<7} {:<24} {}",
+                pack.info.name,
+                pack.info.version,
+                pack.info.region,
+                pack.info.tags.join(","),
+                pack.info.description
+            ),
+            Err(error) => println!("{}", error),
+        }
+    }
+    Ok(())
+}
+
+/// Validates the packs in `dirs`, or every installed pack when `dirs` is
+/// empty, and fails when any of them has a problem.
+pub fn validate_packs(dirs: &[PathBuf]) -> Result<(), ElpError> {
+    let packs: Vec<Result<Pack, ElpError>> = if dirs.is_empty() {
+        installed_packs()?
+    } else {
+        dirs.iter().map(|dir| Pack::load(dir)).collect()
+    };
+
+    let mut failed = 0;
+    for pack in &packs {
+        match pack {
+            Ok(pack) => {
+                let problems = pack.validate();
+                if problems.is_empty() {
+                    println!("{} {}: ok", pack.info.name, pack.info.version);
+                } else {
+                    failed += 1;
+                    println!("{} {}:", pack.info.name, pack.info.version);
+                    for problem in problems {
+                        println!("  {}", problem);
+                    }
+                }
+            }
+            Err(error) => {
+                failed += 1;
+                println!("{}", error);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(ElpError::DataFile {
+            path: dirs.first().cloned().or_else(packs_dir).unwrap_or_default(),
+            message: format!("{} of {} phrase packs are invalid", failed, packs.len()),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    fn write_pack(dir: &std::path::Path, manifest: &str, phrases: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("pack.toml"), manifest).unwrap();
+        std::fs::write(dir.join("phrases.toml"), phrases).unwrap();
+    }
+
+    #[test]
+    fn test_validate_pack() {
+        use crate::packs::Pack;
+
+        let dir = std::env::temp_dir().join(format!("elp-pack-{}", std::process::id()));
+
+        write_pack(
+            &dir.join("good"),
+            "[pack]\nname = \"ground\"\nversion = \"1.0.0\"\nregion = \"ICAO\"\ntags = [\"beginner\"]\n",
+            "[phrases]\ntaxi = [\"{callsign}, taxi to holding point {runway}, {clearance}\"]\n",
+        );
+        let pack = Pack::load(&dir.join("good")).unwrap();
+        assert_eq!(pack.info.name, "ground");
+        assert!(pack.validate().is_empty());
+
+        write_pack(
+            &dir.join("bad"),
+            "[pack]\nname = \"bad pack\"\nversion = \"1\"\nregion = \"EASA\"\n",
+            "[phrases]\ntaxi = [\"{callsign}, taxi to {stand}\"]\nloop = [\"again {loop}\"]\n",
+        );
+        let problems = Pack::load(&dir.join("bad")).unwrap().validate();
+        assert_eq!(problems.len(), 5);
+        assert!(problems.iter().any(|p| p.contains("stand")));
+        assert!(problems.iter().any(|p| p.contains("refers to itself")));
+
+        assert!(Pack::load(&dir.join("missing")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,89 +1,577 @@
-pub fn parse_args() {
-    use crate::elp_service::{get_voices, ConfigElp};
-    use clap::{Arg, Command};
+use std::path::PathBuf;
 
-    let valid_messages = vec!["ANY", "ATIS"]; // Define valid messages as needed
-    let mut valid_voices: Vec<&str> = get_voices().unwrap().keys().cloned().collect();
-    valid_voices.sort();
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Drill,
+    Quiz(String),
+    Readback,
+    Dictation,
+    Controller(Vec<String>),
+    Speak(Vec<String>),
+    Phonetic(String),
+    Scenario(String),
+    Export {
+        format: String,
+        output: Option<PathBuf>,
+    },
+    VoicesList,
+    VoicesTest(Option<String>),
+    Stats,
+    PacksList,
+    PacksValidate(Vec<PathBuf>),
+    PacksInstall(PathBuf),
+    ShowConfig,
+}
+
+pub const QUIZ_KINDS: [&str; 7] = [
+    "blocked",
+    "holding",
+    "traffic",
+    "readability",
+    "squawk",
+    "crosswind",
+    "waypoint",
+];
+
+// transition altitudes in use around the world, in feet
+const TRANSITION_ALTITUDES: std::ops::RangeInclusive<u32> = 1000..=18000;
+
+fn count_arg() -> clap::Arg {
+    clap::Arg::new("count")
+        .short('m')
+        .long("count")
+        .default_value("1")
+        .help("Message count")
+        .value_parser(clap::value_parser!(u32))
+}
+
+fn message_args() -> Vec<clap::Arg> {
+    use clap::Arg;
+    use elp::elp_service::get_mix_names;
+
+    vec![
+        Arg::new("type")
+            .short('t')
+            .long("type")
+            .default_value("ANY")
+            .help("Message type: ANY, ATIS, a pilot call (initial_contact, position_report, ...) or any category of the phrase files"),
+        count_arg(),
+        Arg::new("mix")
+            .short('x')
+            .long("mix")
+            .value_delimiter(',')
+            .help("Comma separated items used in ANY messages (default: all)")
+            .value_parser(get_mix_names()),
+    ]
+}
 
-    let matches = Command::new("elp")
+fn command() -> clap::Command {
+    use clap::{Arg, ArgAction, Command};
+    use elp::controller::REQUESTS;
+    use elp::elp_service::{get_voice_names, BACKENDS};
+    use elp::export::FORMATS;
+    use elp::phonetic::PHONETIC_MODES;
+    use elp::pilot::PILOT_CALLS;
+    use elp::region::REGIONS;
+    use elp::scenario::SCENARIOS;
+
+    let valid_voices = get_voice_names();
+
+    Command::new("elp")
         .about("ELP training tool for pilots")
         .arg(
             Arg::new("callsign")
                 .short('a')
                 .long("callsign")
                 .default_value("A6KIA")
+                .global(true)
                 .help("Call sign"),
         )
-        .arg(
-            Arg::new("type")
-                .short('t')
-                .long("type")
-                .default_value("ANY")
-                .help("Message type")
-                .value_parser(valid_messages),
-        )
-        .arg(
-            Arg::new("count")
-                .short('m')
-                .long("count")
-                .default_value("1")
-                .help("Message count"),
-        )
         .arg(
             Arg::new("rate")
                 .short('r')
                 .long("rate")
                 .default_value("180")
-                .help("Words per minute"),
+                .global(true)
+                .help("Words per minute")
+                .value_parser(clap::value_parser!(u32)),
         )
         .arg(
             Arg::new("pause")
                 .short('p')
                 .long("pause")
                 .default_value("5")
-                .help("Pause between messages"),
+                .global(true)
+                .help("Pause between messages")
+                .value_parser(clap::value_parser!(u32)),
         )
         .arg(
             Arg::new("comma")
                 .short('c')
                 .long("comma")
                 .default_value("0")
-                .help("Pause between blocks"),
+                .global(true)
+                .help("Pause between blocks")
+                .value_parser(clap::value_parser!(u32)),
         )
         .arg(
             Arg::new("sayagain")
                 .short('s')
                 .long("sayagain")
                 .default_value("0")
-                .help("Repeat each message 2 times"),
+                .global(true)
+                .help("Repeat each message 2 times")
+                .value_parser(clap::value_parser!(u32)),
         )
         .arg(
             Arg::new("voice")
                 .short('v')
                 .long("voice")
                 .default_value("ATC0")
+                .global(true)
                 .help("Voice name")
                 .value_parser(valid_voices.clone()),
         )
-        .get_matches();
-
-    let mut config = crate::elp_service::CONFIG.lock().unwrap();
-    config.call_sign = matches.get_one::<String>("callsign").unwrap().to_string();
-    config.msg_type = matches.get_one::<String>("type").unwrap().to_string();
-    config.num_msg = matches.get_one::<String>("count").unwrap().parse().unwrap();
-    config.rate = matches.get_one::<String>("rate").unwrap().parse().unwrap();
-    config.pause = matches.get_one::<String>("pause").unwrap().parse().unwrap();
-    config.comma =
-        ConfigElp::parse_bool(matches.get_one::<String>("comma").unwrap().parse().unwrap());
-    config.sayagain = ConfigElp::parse_bool(
-        matches
-            .get_one::<String>("sayagain")
-            .unwrap()
-            .parse()
-            .unwrap(),
-    );
-    config.voice = matches.get_one::<String>("voice").unwrap().to_string();
-
-    drop(config);
+        .arg(
+            Arg::new("backend")
+                .short('b')
+                .long("backend")
+                .default_value("say")
+                .global(true)
+                .help("Speech backend: say (system TTS), silent (print only)")
+                .value_parser(BACKENDS),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .global(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Config file (default: $XDG_CONFIG_HOME/elp/config.toml)"),
+        )
+        .arg(
+            Arg::new("phrases")
+                .long("phrases")
+                .global(true)
+                .action(ArgAction::Append)
+                .help("Phrase file with extra templates, may be repeated"),
+        )
+        .arg(
+            Arg::new("pack")
+                .long("pack")
+                .global(true)
+                .action(ArgAction::Append)
+                .help("Installed phrase pack to use, may be repeated"),
+        )
+        .arg(
+            Arg::new("region")
+                .long("region")
+                .default_value("ICAO")
+                .global(true)
+                .help("Phraseology: ICAO, FAA or CAP413 (UK)")
+                .value_parser(REGIONS),
+        )
+        .arg(
+            Arg::new("transition-altitude")
+                .long("transition-altitude")
+                .global(true)
+                .help("Transition altitude of the airport in feet (default: by region)")
+                .value_parser(clap::value_parser!(u32).range(
+                    *TRANSITION_ALTITUDES.start() as i64..=*TRANSITION_ALTITUDES.end() as i64,
+                )),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .global(true)
+                .help("Named profile from the config file"),
+        )
+        .args(message_args())
+        .subcommand(
+            Command::new("drill")
+                .about("Print and speak messages (default when no command is given)")
+                .args(message_args()),
+        )
+        .subcommand(
+            Command::new("quiz")
+                .about("Interactive exercises scored after each answer")
+                .args(message_args())
+                .arg(
+                    Arg::new("kind")
+                        .short('k')
+                        .long("kind")
+                        .default_value("blocked")
+                        .help("Exercise: blocked (recognise blocked transmissions, answer \"say again\"), holding (name the holding entry), traffic (locate traffic and respond), readability (radio checks with degraded audio), squawk (emergency and reserved codes), crosswind (wind components), waypoint (write down a spelled waypoint)")
+                        .value_parser(QUIZ_KINDS),
+                ),
+        )
+        .subcommand(
+            Command::new("readback")
+                .about("Listen to an instruction and type the readback")
+                .args(message_args()),
+        )
+        .subcommand(
+            Command::new("dictation")
+                .about("Listen to a message and type it word for word")
+                .args(message_args()),
+        )
+        .subcommand(
+            Command::new("controller")
+                .about("Listen to pilot requests and type the controller response")
+                .arg(count_arg())
+                .arg(
+                    Arg::new("request")
+                        .short('k')
+                        .long("request")
+                        .value_delimiter(',')
+                        .help("Comma separated pilot requests (default: all)")
+                        .value_parser(REQUESTS),
+                ),
+        )
+        .subcommand(
+            Command::new("speak")
+                .about("Read a situation and type the pilot transmission")
+                .arg(count_arg())
+                .arg(
+                    Arg::new("call")
+                        .short('k')
+                        .long("call")
+                        .value_delimiter(',')
+                        .help("Comma separated pilot calls (default: all)")
+                        .value_parser(PILOT_CALLS),
+                ),
+        )
+        .subcommand(
+            Command::new("phonetic")
+                .about("Type spoken phonetic spellings or spell shown characters")
+                .arg(count_arg())
+                .arg(
+                    Arg::new("mode")
+                        .default_value("listen")
+                        .help("listen (type the characters heard) or spell (type the phonetic words)")
+                        .value_parser(PHONETIC_MODES),
+                ),
+        )
+        .subcommand(
+            Command::new("scenario")
+                .about("Play the messages of a flight in order")
+                .arg(
+                    Arg::new("name")
+                        .default_value("full")
+                        .help("Scenario name")
+                        .value_parser(SCENARIOS),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Write generated messages to a file or stdout")
+                .args(message_args())
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .default_value("text")
+                        .help("Output format")
+                        .value_parser(FORMATS),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Output file (default: stdout)"),
+                ),
+        )
+        .subcommand(
+            Command::new("voices")
+                .about("List or test the available voices")
+                .subcommand_required(true)
+                .subcommand(Command::new("list").about("List voices"))
+                .subcommand(
+                    Command::new("test")
+                        .about("Speak a radio check with one or all voices")
+                        .arg(
+                            Arg::new("name")
+                                .help("Voice name (default: all)")
+                                .value_parser(valid_voices.clone()),
+                        ),
+                ),
+        )
+        .subcommand(Command::new("stats").about("Show results of recorded sessions"))
+        .subcommand(
+            Command::new("config")
+                .about("Inspect the configuration")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("show")
+                        .about("Print the configuration after config file, profile and flags")
+                        .args(message_args()),
+                ),
+        )
+        .subcommand(
+            Command::new("packs")
+                .about("Manage phrase packs")
+                .subcommand_required(true)
+                .subcommand(Command::new("list").about("List installed phrase packs"))
+                .subcommand(
+                    Command::new("validate")
+                        .about("Check manifests and templates of phrase packs")
+                        .arg(
+                            Arg::new("dir")
+                                .num_args(0..)
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("Pack directories (default: all installed packs)"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("install")
+                        .about("Validate a phrase pack directory and install it")
+                        .arg(
+                            Arg::new("dir")
+                                .required(true)
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("Pack directory containing pack.toml"),
+                        ),
+                ),
+        )
+}
+
+pub fn parse_args() -> Result<(Action, elp::elp_service::ConfigElp), elp::error::ElpError> {
+    config_from(&command().get_matches())
+}
+
+fn config_from(
+    matches: &clap::ArgMatches,
+) -> Result<(Action, elp::elp_service::ConfigElp), elp::error::ElpError> {
+    use clap::parser::ValueSource;
+    use elp::config_file::{default_config_path, ConfigFile};
+    use elp::elp_service::{get_mix_names, get_voice_names, ConfigElp, BACKENDS};
+    use elp::error::ElpError;
+    use elp::region::Region;
+
+    let valid_voices = get_voice_names();
+    let invalid = |message: String| Err(ElpError::InvalidArgument(message));
+
+    // message options before the command name belong to the default drill
+    if matches.subcommand().is_some() {
+        if let Some(id) = ["type", "count", "mix"]
+            .into_iter()
+            .find(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        {
+            return invalid(format!("--{} must follow the command name", id));
+        }
+    }
+
+    // options of the selected command, global options are propagated down to it
+    let (action, args) = match matches.subcommand() {
+        Some(("quiz", sub)) => (
+            Action::Quiz(sub.get_one::<String>("kind").unwrap().to_string()),
+            sub,
+        ),
+        Some(("readback", sub)) => (Action::Readback, sub),
+        Some(("dictation", sub)) => (Action::Dictation, sub),
+        Some(("speak", sub)) => (
+            Action::Speak(
+                sub.get_many::<String>("call")
+                    .map(|kinds| kinds.cloned().collect())
+                    .unwrap_or_default(),
+            ),
+            sub,
+        ),
+        Some(("controller", sub)) => (
+            Action::Controller(
+                sub.get_many::<String>("request")
+                    .map(|kinds| kinds.cloned().collect())
+                    .unwrap_or_default(),
+            ),
+            sub,
+        ),
+        Some(("phonetic", sub)) => (
+            Action::Phonetic(sub.get_one::<String>("mode").unwrap().to_string()),
+            sub,
+        ),
+        Some(("scenario", sub)) => (
+            Action::Scenario(sub.get_one::<String>("name").unwrap().to_string()),
+            sub,
+        ),
+        Some(("export", sub)) => (
+            Action::Export {
+                format: sub.get_one::<String>("format").unwrap().to_string(),
+                output: sub.get_one::<PathBuf>("output").cloned(),
+            },
+            sub,
+        ),
+        Some(("voices", sub)) => match sub.subcommand() {
+            Some(("test", test)) => (
+                Action::VoicesTest(test.get_one::<String>("name").cloned()),
+                test,
+            ),
+            Some((_, list)) => (Action::VoicesList, list),
+            None => (Action::VoicesList, sub),
+        },
+        Some(("stats", sub)) => (Action::Stats, sub),
+        Some(("config", sub)) => (
+            Action::ShowConfig,
+            sub.subcommand().map_or(sub, |(_, show)| show),
+        ),
+        Some(("packs", sub)) => match sub.subcommand() {
+            Some(("validate", validate)) => (
+                Action::PacksValidate(
+                    validate
+                        .get_many::<PathBuf>("dir")
+                        .map(|dirs| dirs.cloned().collect())
+                        .unwrap_or_default(),
+                ),
+                validate,
+            ),
+            Some(("install", install)) => (
+                Action::PacksInstall(install.get_one::<PathBuf>("dir").unwrap().clone()),
+                install,
+            ),
+            Some((_, list)) => (Action::PacksList, list),
+            None => (Action::PacksList, sub),
+        },
+        Some((_, sub)) => (Action::Drill, sub),
+        None => (Action::Drill, matches),
+    };
+    let get = |id: &str| -> Option<String> {
+        args.try_get_one::<String>(id)
+            .ok()
+            .flatten()
+            .map(|x| x.to_string())
+    };
+    let get_u32 = |id: &str| -> u32 { *args.get_one::<u32>(id).unwrap() };
+
+    let mut config = ConfigElp {
+        call_sign: get("callsign").unwrap(),
+        rate: get_u32("rate"),
+        pause: get_u32("pause"),
+        comma: ConfigElp::parse_bool(get_u32("comma")),
+        sayagain: ConfigElp::parse_bool(get_u32("sayagain")),
+        voice: get("voice").unwrap(),
+        backend: get("backend").unwrap(),
+        region: Region::parse(&get("region").unwrap()).unwrap(),
+        transition_altitude: args.get_one::<u32>("transition-altitude").cloned(),
+        ..Default::default()
+    };
+    if let Some(msg_type) = get("type") {
+        config.msg_type = msg_type;
+    }
+    if let Ok(Some(count)) = args.try_get_one::<u32>("count") {
+        config.num_msg = *count;
+    }
+    if let Ok(Some(mix)) = args.try_get_many::<String>("mix") {
+        config.mix = mix.cloned().collect();
+    }
+    if let Ok(Some(phrases)) = args.try_get_many::<String>("phrases") {
+        config.phrases = phrases.cloned().collect();
+    }
+    if let Ok(Some(packs)) = args.try_get_many::<String>("pack") {
+        config.packs = packs.cloned().collect();
+    }
+
+    // config file values override the built-in defaults, command line flags override both
+    let config_path = match args.get_one::<PathBuf>("config") {
+        Some(path) => Some(path.clone()),
+        None => default_config_path().filter(|path| path.exists()),
+    };
+    let profile_name = args.get_one::<String>("profile");
+
+    if let Some(path) = config_path {
+        let file = ConfigFile::load(&path)?;
+        let profile = file.resolve(profile_name.map(|name| name.as_str()))?;
+        let from_cli: Vec<&str> = args
+            .ids()
+            .map(|id| id.as_str())
+            .filter(|id| args.value_source(id) == Some(ValueSource::CommandLine))
+            .collect();
+        profile.apply(&mut config, &from_cli);
+    } else if let Some(name) = profile_name {
+        return invalid(format!(
+            "profile '{}' requested but no config file found",
+            name
+        ));
+    }
+
+    if !valid_voices.contains(&config.voice.as_str()) {
+        return Err(ElpError::UnknownVoice(config.voice));
+    }
+    if !BACKENDS.contains(&config.backend.as_str()) {
+        return invalid(format!("unknown backend '{}'", config.backend));
+    }
+    if let Some(item) = config
+        .mix
+        .iter()
+        .find(|m| !get_mix_names().contains(&m.as_str()))
+    {
+        return invalid(format!("unknown mix item '{}'", item));
+    }
+    if let Some(feet) = config
+        .transition_altitude
+        .filter(|feet| !TRANSITION_ALTITUDES.contains(feet))
+    {
+        return invalid(format!(
+            "transition altitude {} outside {} to {} feet",
+            feet,
+            TRANSITION_ALTITUDES.start(),
+            TRANSITION_ALTITUDES.end()
+        ));
+    }
+
+    Ok((action, config))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_global_options() {
+        use crate::parse_args::{command, config_from, Action};
+        use elp::region::Region;
+
+        let parse = |args: &[&str]| {
+            let matches = command().try_get_matches_from(args).unwrap();
+            config_from(&matches)
+        };
+
+        let (action, config) = parse(&["elp", "--region", "FAA", "export", "-m", "2"]).unwrap();
+        assert!(matches!(action, Action::Export { .. }));
+        assert_eq!(config.region, Region::Faa);
+        assert_eq!(config.num_msg, 2);
+
+        let (action, config) = parse(&["elp", "quiz", "--rate", "200", "-b", "silent"]).unwrap();
+        assert_eq!(action, Action::Quiz("blocked".to_string()));
+        assert_eq!((config.rate, config.backend.as_str()), (200, "silent"));
+
+        assert!(parse(&["elp", "-m", "3", "export"]).is_err());
+
+        let (action, config) = parse(&["elp", "config", "show", "-a", "DLHAB"]).unwrap();
+        assert_eq!(action, Action::ShowConfig);
+        assert_eq!(config.call_sign, "DLHAB");
+        assert!(command()
+            .try_get_matches_from(["elp", "export", "--show-config"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_transition_altitude_from_config() {
+        use crate::parse_args::{command, config_from};
+        use elp::error::ElpError;
+
+        let path = std::env::temp_dir().join(format!("elp-test-{}.toml", std::process::id()));
+        let parse = |text: &str| {
+            std::fs::write(&path, text).unwrap();
+            let config = path.to_str().unwrap();
+            let matches = command()
+                .try_get_matches_from(["elp", "--config", config, "export"])
+                .unwrap();
+            config_from(&matches)
+        };
+
+        let (_, config) = parse("[defaults]\ntransition_altitude = 6000\n").unwrap();
+        assert_eq!(config.transition_altitude, Some(6000));
+        for feet in [300, 45000] {
+            let result = parse(&format!("[defaults]\ntransition_altitude = {}\n", feet));
+            assert!(matches!(result, Err(ElpError::InvalidArgument(_))));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
 }
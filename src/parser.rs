@@ -0,0 +1,353 @@
+use crate::phonetic::character;
+use crate::runway::{Runway, Side};
+
+/// A value an instruction or its readback carries.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    FlightLevel(u32),
+    /// Altitude in feet.
+    Altitude(u32),
+    Heading(u32),
+    Turn {
+        direction: &'static str,
+        heading: u32,
+    },
+    /// Speed in knots.
+    Speed(u32),
+    Squawk(u32),
+    /// Channel name in kHz, 118725 for "118.725".
+    Frequency(u32),
+    /// QNH in hectopascals or altimeter setting in hundredths of an inch.
+    Qnh(u32),
+    Runway(Runway),
+    Wind {
+        direction: u32,
+        speed: u32,
+    },
+}
+
+fn is_number(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// Lower case words and numbers: spelled digits ("tree", "niner") become
+/// digits, digits said one by one are joined ("one one eight decimal
+/// seven" is "118.7"), "fl350" is split into "fl" and "350" and
+/// "5 thousand 5 hundred" is "5500".
+pub fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = element_tokens(text);
+    tokens.retain(|token| token != ",");
+    tokens
+}
+
+/// Like `tokens`, with "," between the comma separated elements.
+fn element_tokens(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in text
+        .to_lowercase()
+        .replace('!', "")
+        // a comma ends a number: "zero, one" is not "01"
+        .replace([',', ';'], " , ")
+        .split_whitespace()
+    {
+        let word = word.trim_end_matches('.');
+        if word == "decimal" || word == "point" {
+            words.push(".".to_string());
+            continue;
+        }
+        if let Some(c) = character(word).filter(|c| c.is_ascii_digit()) {
+            words.push(c.to_string());
+            continue;
+        }
+        // letters and digits typed together: "fl350", "27l"
+        let mut part = String::new();
+        for c in word.chars() {
+            if !part.is_empty() && is_number(&part) != is_number(&c.to_string()) {
+                words.push(std::mem::take(&mut part));
+            }
+            part.push(c);
+        }
+        if !part.is_empty() {
+            words.push(part);
+        }
+    }
+
+    let mut joined: Vec<String> = Vec::new();
+    for word in words {
+        match joined.last_mut() {
+            Some(last) if is_number(last) && is_number(&word) => last.push_str(&word),
+            _ => joined.push(word),
+        }
+    }
+
+    let mut tokens: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < joined.len() {
+        let number: Option<u32> = joined[i].parse().ok();
+        let next = joined.get(i + 1).map(String::as_str);
+        match (number, next) {
+            // numbers too large for feet are left as typed
+            (Some(thousands), Some("thousand")) if thousands.checked_mul(1000).is_some() => {
+                let mut feet = thousands * 1000;
+                i += 2;
+                let hundreds: Option<u32> = joined.get(i).and_then(|t| t.parse().ok());
+                if let (Some(hundreds), Some("hundred")) =
+                    (hundreds, joined.get(i + 1).map(String::as_str))
+                {
+                    if let Some(sum) = hundreds.checked_mul(100).and_then(|h| h.checked_add(feet)) {
+                        feet = sum;
+                        i += 2;
+                    }
+                }
+                tokens.push(feet.to_string());
+            }
+            (Some(hundreds), Some("hundred")) if hundreds.checked_mul(100).is_some() => {
+                tokens.push((hundreds * 100).to_string());
+                i += 2;
+            }
+            _ => {
+                tokens.push(joined[i].clone());
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+fn side(token: &str) -> Option<Side> {
+    match token {
+        "left" | "l" => Some(Side::Left),
+        "right" | "r" => Some(Side::Right),
+        "centre" | "center" | "c" => Some(Side::Centre),
+        _ => None,
+    }
+}
+
+fn runway(number: &str, side: Option<Side>) -> Option<Value> {
+    if number.len() > 2 {
+        return None;
+    }
+    let number: u32 = number.parse().ok()?;
+    if (1..=36).contains(&number) {
+        Some(Value::Runway(Runway { number, side }))
+    } else {
+        None
+    }
+}
+
+/// Values found in a free text instruction or readback: "climbing FL350",
+/// "flight level tree fife zero", "one one eight decimal seven two five",
+/// "QNH 1013", "left 27".
+pub fn parse_values(text: &str) -> Vec<Value> {
+    let tokens = element_tokens(text);
+    let token = |i: usize| tokens.get(i).map(String::as_str).unwrap_or("");
+    let number = |i: usize| -> Option<u32> {
+        if is_number(token(i)) {
+            token(i).parse().ok()
+        } else {
+            None
+        }
+    };
+    let mut values = Vec::new();
+    let mut i = 0;
+    // tokens before `start` belong to a value already found
+    let mut start = 0;
+
+    while i < tokens.len() {
+        let (value, used) = match token(i) {
+            "fl" => (number(i + 1).map(Value::FlightLevel), 2),
+            "flight" if token(i + 1) == "level" => (number(i + 2).map(Value::FlightLevel), 3),
+            "heading" | "hdg" => {
+                // "left heading 270", "turn left, heading 270"
+                let direction = |j: usize| {
+                    ["left", "right"]
+                        .into_iter()
+                        .find(|d| j >= start && token(j) == *d)
+                };
+                let turn = match i {
+                    0 => None,
+                    1 | 2 => direction(i - 1),
+                    _ => direction(i - 1).or(if token(i - 3) == "turn" && i - 3 >= start {
+                        direction(i - 2)
+                    } else {
+                        None
+                    }),
+                };
+                let value = number(i + 1).map(|heading| match turn {
+                    Some(direction) => Value::Turn { direction, heading },
+                    None => Value::Heading(heading),
+                });
+                (value, 2)
+            }
+            "squawk" | "squawking" | "sqk" => (number(i + 1).map(Value::Squawk), 2),
+            "qnh" | "altimeter" => (number(i + 1).map(Value::Qnh), 2),
+            "speed" | "spd" => (number(i + 1).map(Value::Speed), 2),
+            "altitude" => (number(i + 1).map(Value::Altitude), 2),
+            // "climbing 5500", "maintain 800"
+            "climb" | "climbing" | "descend" | "descending" | "maintain" | "maintaining" => {
+                let at = if token(i + 1) == "to" { i + 2 } else { i + 1 };
+                let value = number(at)
+                    .filter(|_| (3..=5).contains(&token(at).len()))
+                    .map(Value::Altitude);
+                (value, at - i + 1)
+            }
+            "runway" | "rwy" => {
+                let side = side(token(i + 2));
+                let value = runway(token(i + 1), side);
+                (value, if side.is_some() { 3 } else { 2 })
+            }
+            "wind" => match (number(i + 1), number(i + 2), number(i + 3)) {
+                (Some(direction), Some(speed), _) => (Some(Value::Wind { direction, speed }), 3),
+                // "wind 270 degrees 15 knots", "wind 270 at 15"
+                (Some(direction), None, Some(speed)) => (Some(Value::Wind { direction, speed }), 4),
+                _ => (None, 1),
+            },
+            word if side(word).is_some() && word.len() > 1 && is_number(token(i + 1)) => {
+                match token(i + 1).len() {
+                    // "left 27" is a runway, "left 270" a turn
+                    1 | 2 => (runway(token(i + 1), side(word)), 2),
+                    _ => {
+                        let direction = ["left", "right"].into_iter().find(|d| *d == word);
+                        let value = direction
+                            .zip(number(i + 1))
+                            .map(|(direction, heading)| Value::Turn { direction, heading });
+                        (value, 2)
+                    }
+                }
+            }
+            // gusts are not a speed to read back
+            "gusting" | "gusts" => {
+                i += 2;
+                continue;
+            }
+            word if is_number(word) => {
+                let next = token(i + 1);
+                if (next == "degrees" || next == "at") && number(i + 2).is_some() {
+                    // "touchdown 270 degrees 12 knots", "midfield 270 at 12"
+                    let value = number(i)
+                        .zip(number(i + 2))
+                        .map(|(direction, speed)| Value::Wind { direction, speed });
+                    (value, 3)
+                } else if word.contains('.') {
+                    let (mhz, khz) = word.split_once('.').unwrap();
+                    let khz = format!("{:0<3}", khz);
+                    let value = match (mhz.parse::<u32>(), khz.parse::<u32>()) {
+                        (Ok(mhz), Ok(khz)) if (118..=136).contains(&mhz) && khz < 1000 => {
+                            Some(Value::Frequency(mhz * 1000 + khz))
+                        }
+                        _ => None,
+                    };
+                    (value, 1)
+                } else if next == "feet" || next == "ft" {
+                    (number(i).map(Value::Altitude), 2)
+                } else if next == "knots" || next == "kts" || next == "kt" {
+                    (number(i).map(Value::Speed), 2)
+                } else if side(next).is_some() {
+                    (runway(word, side(next)), 2)
+                } else {
+                    (None, 1)
+                }
+            }
+            _ => (None, 1),
+        };
+        if let Some(value) = value {
+            values.push(value);
+            i += used;
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_tokens() {
+        use crate::parser::tokens;
+
+        assert_eq!(tokens("climbing FL350"), ["climbing", "fl", "350"]);
+        assert_eq!(tokens("one one eight decimal seven two five"), ["118.725"]);
+        assert_eq!(tokens("5 thousand 5 hundred feet"), ["5500", "feet"]);
+        assert_eq!(tokens("runway 27L."), ["runway", "27", "l"]);
+    }
+
+    #[test]
+    fn test_parse_values() {
+        use crate::parser::{parse_values, tokens, Value};
+        use crate::runway::Runway;
+
+        let runway = |designator| Value::Runway(Runway::parse(designator).unwrap());
+        assert_eq!(parse_values("climbing FL350"), [Value::FlightLevel(350)]);
+        assert_eq!(
+            parse_values("flight level tree fife zero"),
+            [Value::FlightLevel(350)]
+        );
+        assert_eq!(
+            parse_values("munich tower one one eight decimal seven two five"),
+            [Value::Frequency(118725)]
+        );
+        assert_eq!(parse_values("118.1"), [Value::Frequency(118100)]);
+        assert_eq!(parse_values("QNH 1013"), [Value::Qnh(1013)]);
+        assert_eq!(parse_values("left 27"), [runway("27L")]);
+        assert_eq!(
+            parse_values("runway 09 right, cleared to land"),
+            [runway("09R")]
+        );
+        assert_eq!(
+            parse_values("turn left heading 270, speed 180 knots"),
+            [
+                Value::Turn {
+                    direction: "left",
+                    heading: 270
+                },
+                Value::Speed(180)
+            ]
+        );
+        assert_eq!(
+            parse_values("descend altitude 4 thousand 5 hundred feet, squawk 0033"),
+            [Value::Altitude(4500), Value::Squawk(33)]
+        );
+        assert_eq!(
+            parse_values("wind 270 degrees 15 knots"),
+            [Value::Wind {
+                direction: 270,
+                speed: 15
+            }]
+        );
+        assert_eq!(
+            parse_values("wind touchdown 270 at 12 gusts 25"),
+            [Value::Wind {
+                direction: 270,
+                speed: 12
+            }]
+        );
+        assert!(parse_values("engine start-up approved").is_empty());
+
+        assert_eq!(
+            parse_values("runway 27 left heading 090"),
+            [runway("27L"), Value::Heading(90)]
+        );
+        assert_eq!(
+            parse_values("turn right, heading 180"),
+            [Value::Turn {
+                direction: "right",
+                heading: 180
+            }]
+        );
+        assert_eq!(parse_values("climbing 5500"), [Value::Altitude(5500)]);
+        assert_eq!(
+            parse_values("descending to 3 thousand"),
+            [Value::Altitude(3000)]
+        );
+        assert_eq!(
+            parse_values("altitude 5000000 thousand"),
+            [Value::Altitude(5000000)]
+        );
+        assert_eq!(
+            tokens("5000000 thousand 9 hundred"),
+            ["5000000", "thousand", "900"]
+        );
+    }
+}
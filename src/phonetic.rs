@@ -0,0 +1,195 @@
+use crate::elp_service::{ask, get_alfabet, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::transponder::discrete_code;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// listen: characters are spoken, the trainee types them; spell: characters
+/// are shown, the trainee types the phonetic words.
+pub const PHONETIC_MODES: [&str; 2] = ["listen", "spell"];
+
+const PREFIXES: [&str; 6] = ["D-", "G-", "F-", "HB-", "OE-", "A6-"];
+
+// spellings heard besides the ones of the alphabet
+const VARIANTS: [(&str, char); 14] = [
+    ("alpha", 'A'),
+    ("juliet", 'J'),
+    ("x-ray", 'X'),
+    ("one", '1'),
+    ("two", '2'),
+    ("three", '3'),
+    ("tree", '3'),
+    ("four", '4'),
+    ("fower", '4'),
+    ("fife", '5'),
+    ("eight", '8'),
+    ("nine", '9'),
+    ("niner", '9'),
+    ("zero", '0'),
+];
+
+/// Character of a phonetic word, "Tree" is '3'.
+pub fn character(word: &str) -> Option<char> {
+    let word = word.to_lowercase();
+    get_alfabet()
+        .into_iter()
+        .filter(|(c, _)| c.len() == 1 && c != &".")
+        .map(|(c, w)| (w, c.chars().next().unwrap()))
+        .chain(VARIANTS)
+        .find(|(w, _)| *w == word)
+        .map(|(_, c)| c)
+}
+
+/// Phonetic words of the letters and digits of `text`: "D-EA1" is
+/// "delta echo alfa wun".
+pub fn spelling(text: &str) -> String {
+    let alfabet = get_alfabet();
+    text.chars()
+        .filter_map(|c| alfabet.get(c.to_string().as_str()).copied())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Registration, waypoint or squawk code to spell.
+pub fn get_item<R: Rng>(generator: &mut Generator<R>) -> String {
+    let region = generator.config().region;
+    let rng = generator.rng();
+    match rng.gen_range(0..4) {
+        0 => {
+            let letters: String = (0..4).map(|_| rng.gen_range('A'..='Z')).collect();
+            format!("{}{}", PREFIXES.choose(rng).unwrap(), letters)
+        }
+        1 => format!(
+            "N{}{}{}",
+            rng.gen_range(1..=999),
+            rng.gen_range('A'..='Z'),
+            rng.gen_range('A'..='Z')
+        ),
+        2 => format!("{:04}", discrete_code(region, rng)),
+        _ => generator.get_waypoint().replace('!', ""),
+    }
+}
+
+/// Letters and digits of `expected` as (character, correct) pairs, the
+/// answer is compared position by position.
+pub fn compare(expected: &str, answer: &[char]) -> Vec<(char, bool)> {
+    expected
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .enumerate()
+        .map(|(i, c)| (c, answer.get(i) == Some(&c)))
+        .collect()
+}
+
+/// Characters typed in listen mode or spelled in spell mode.
+fn answer_chars(mode: &str, answer: &str) -> Vec<char> {
+    match mode {
+        "spell" => answer
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .map(|w| character(w).unwrap_or('?'))
+            .collect(),
+        _ => answer
+            .to_uppercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect(),
+    }
+}
+
+pub fn run_phonetic_session<R: Rng>(
+    generator: &mut Generator<R>,
+    mode: &str,
+) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let mut per_char: BTreeMap<char, (u32, u32)> = BTreeMap::new();
+    let mut seconds = 0.0;
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        let item = get_item(generator);
+        // timed from the end of the speech or from showing the item
+        let (answer, start) = if mode == "spell" {
+            println!("{}", item);
+            let start = Instant::now();
+            (
+                ask(&format!("[{}/{}] Spelling: ", n, config.num_msg))?,
+                start,
+            )
+        } else {
+            crate::elp_service::be_ready(config.pause);
+            say(
+                prepare_message_for_say(&item.replace('-', ""), config.region),
+                false,
+                &mut config,
+            )?;
+            let start = Instant::now();
+            (
+                ask(&format!("[{}/{}] Characters: ", n, config.num_msg))?,
+                start,
+            )
+        };
+        let elapsed = start.elapsed().as_secs_f64();
+        seconds += elapsed;
+
+        let typed = answer_chars(mode, &answer);
+        let result = compare(&item, &typed);
+        for (c, ok) in &result {
+            let entry = per_char.entry(*c).or_insert((0, 0));
+            entry.0 += *ok as u32;
+            entry.1 += 1;
+        }
+        if result.iter().all(|(_, ok)| *ok) && typed.len() == result.len() {
+            println!("Correct. ({:.1} s)", elapsed);
+            score += 1;
+        } else {
+            println!("{}: {} ({:.1} s)", item, spelling(&item), elapsed);
+        }
+    }
+
+    println!(
+        "Score: {}/{}, {:.1} s per answer",
+        score,
+        config.num_msg,
+        seconds / config.num_msg.max(1) as f64
+    );
+    let missed: Vec<String> = per_char
+        .iter()
+        .filter(|(_, (correct, total))| correct < total)
+        .map(|(c, (correct, total))| format!("{} {}/{}", c, correct, total))
+        .collect();
+    if !missed.is_empty() {
+        println!("Missed characters: {}", missed.join(", "));
+    }
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_character() {
+        use crate::phonetic::{character, spelling};
+
+        assert_eq!(character("Alfa"), Some('A'));
+        assert_eq!(character("alpha"), Some('A'));
+        assert_eq!(character("niner"), Some('9'));
+        assert_eq!(character("tree"), Some('3'));
+        assert_eq!(character("decimal"), None);
+        assert_eq!(spelling("D-EA1"), "delta echo alfa wun");
+    }
+
+    #[test]
+    fn test_compare() {
+        use crate::phonetic::compare;
+
+        let result = compare("D-EABC", &['D', 'E', 'A', 'P', 'C']);
+        assert_eq!(result.len(), 5);
+        assert_eq!(result[3], ('B', false));
+        assert_eq!(result.iter().filter(|(_, ok)| *ok).count(), 4);
+        assert!(compare("4521", &['4', '5', '2', '1'])
+            .iter()
+            .all(|(_, ok)| *ok));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::readback::check_readback;
+use crate::region::Region;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Pilot-initiated transmissions, each one is also a message type.
+pub const PILOT_CALLS: [&str; 4] = [
+    "initial_contact",
+    "position_report",
+    "weather_deviation",
+    "ready_for_departure",
+];
+
+/// A pilot transmission and the situation it describes, the situation is
+/// the prompt of the speaking drill.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PilotReport {
+    pub situation: String,
+    pub text: String,
+}
+
+pub fn is_pilot_call(msg_type: &str) -> bool {
+    PILOT_CALLS.contains(&msg_type)
+}
+
+/// Times of two points, the second one 5 to 30 minutes later: ("1425", "1442").
+fn times<R: Rng>(generator: &mut Generator<R>) -> (String, String) {
+    let rng = generator.rng();
+    let first = rng.gen_range(0..24 * 60);
+    let second = (first + rng.gen_range(5..=30)) % (24 * 60);
+    let hhmm = |minutes: u32| format!("{:02}{:02}", minutes / 60, minutes % 60);
+    (hhmm(first), hhmm(second))
+}
+
+pub fn get_pilot_report<R: Rng>(kind: &str, generator: &mut Generator<R>) -> Option<PilotReport> {
+    let call_sign = generator.config().call_sign.clone();
+    let region = generator.config().region;
+
+    let report = match kind {
+        "initial_contact" => {
+            let station = generator.get_station();
+            let altitude = generator.get_altitude();
+            let level = generator.get_fl();
+            let text = match region {
+                Region::Faa => format!("{}, {}, with you climbing {}", station, call_sign, level),
+                Region::Icao | Region::Cap413 => format!(
+                    "{}, {}, passing {}, climbing {}",
+                    station, call_sign, altitude, level
+                ),
+            };
+            PilotReport {
+                situation: format!(
+                    "First call to {}, passing {} climbing to {}",
+                    station, altitude, level
+                ),
+                text,
+            }
+        }
+        "position_report" => {
+            let station = generator.get_station();
+            let waypoint = generator.get_waypoint();
+            let next = generator.get_waypoint();
+            let level = generator.get_fl();
+            let (time, estimate) = times(generator);
+            PilotReport {
+                situation: format!(
+                    "Over {} at {} at {}, next point {} expected at {}",
+                    waypoint, time, level, next, estimate
+                ),
+                text: format!(
+                    "{}, {}, {} at {}, {}, estimating {} at {}",
+                    station, call_sign, waypoint, time, level, next, estimate
+                ),
+            }
+        }
+        "weather_deviation" => {
+            let station = generator.get_station();
+            let miles = generator.rng().gen_range(1..=6) * 5;
+            let side = ["left", "right"].choose(generator.rng()).unwrap();
+            PilotReport {
+                situation: format!(
+                    "Thunderstorm ahead, you need up to {} miles {} of track",
+                    miles, side
+                ),
+                text: format!(
+                    "{}, {}, request weather deviation up to {} miles {} of track",
+                    station, call_sign, miles, side
+                ),
+            }
+        }
+        "ready_for_departure" => {
+            let airport = generator.get_slot("airport").unwrap();
+            let runway = generator.get_rw();
+            let text = match region {
+                Region::Faa => format!(
+                    "{} tower, {}, holding short {}, ready for departure",
+                    airport, call_sign, runway
+                ),
+                Region::Icao | Region::Cap413 => format!(
+                    "{} tower, {}, holding point {}, ready for departure",
+                    airport, call_sign, runway
+                ),
+            };
+            PilotReport {
+                situation: format!("Holding at {} at {}, ready to go", runway, airport),
+                text,
+            }
+        }
+        _ => return None,
+    };
+    Some(report)
+}
+
+/// Speaking drill: the situation is shown, the trainee types the pilot
+/// transmission and hears the expected one in the `Pilot` voice.
+/// `kinds` selects the calls, empty means all of them.
+pub fn run_speaking_session<R: Rng>(
+    generator: &mut Generator<R>,
+    kinds: &[String],
+) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    config.voice = "Pilot".to_string();
+    let kinds: Vec<&str> = PILOT_CALLS
+        .iter()
+        .filter(|kind| kinds.is_empty() || kinds.iter().any(|k| k == *kind))
+        .cloned()
+        .collect();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        let kind = kinds.choose(generator.rng()).unwrap();
+        let report = get_pilot_report(kind, generator).unwrap();
+
+        println!("{}", report.situation.replace('!', ""));
+        let answer = ask(&format!("[{}/{}] Pilot: ", n, config.num_msg))?;
+        let missing = check_readback(&report.text, &answer);
+
+        println!("{}", report.text.replace('!', ""));
+        say(
+            prepare_message_for_say(&report.text, config.region),
+            false,
+            &mut config,
+        )?;
+        if missing.is_empty() {
+            println!("Correct.");
+            score += 1;
+        } else {
+            for element in missing {
+                println!("  missing or incorrect: {}", element);
+            }
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_pilot_reports() {
+        use crate::elp_service::{ConfigElp, Generator};
+        use crate::pilot::{get_pilot_report, PILOT_CALLS};
+        use crate::readback::check_readback;
+        use crate::region::Region;
+
+        let config = ConfigElp {
+            call_sign: "A6KIA".to_string(),
+            ..Default::default()
+        };
+        let mut generator = Generator::new(config, rand::thread_rng());
+
+        for kind in PILOT_CALLS {
+            let report = get_pilot_report(kind, &mut generator).unwrap();
+            assert!(report.text.contains(", A6KIA, "));
+            assert!(check_readback(&report.text, &report.text).is_empty());
+            assert!(generator.get_message(kind).contains("A6KIA"));
+        }
+
+        let report = get_pilot_report("position_report", &mut generator).unwrap();
+        assert!(report.text.contains(" estimating "));
+
+        generator.config_mut().region = Region::Faa;
+        let report = get_pilot_report("initial_contact", &mut generator).unwrap();
+        assert!(report.text.contains("with you climbing flight level"));
+        assert!(get_pilot_report("taxi", &mut generator).is_none());
+    }
+}
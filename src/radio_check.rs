@@ -0,0 +1,183 @@
+use crate::blocked::{spawn_play, wait, write_wav, SAMPLE_RATE};
+use crate::elp_service::{ask, prepare_message_for_say, spawn_say, Generator};
+use crate::error::ElpError;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Readability scale, index 0 is readability 1.
+pub const READABILITY: [&str; 5] = [
+    "unreadable",
+    "readable now and then",
+    "readable but with difficulty",
+    "readable",
+    "perfectly readable",
+];
+
+// share of words lost and noise level for readability 1 to 5
+const DROPPED: [f64; 5] = [0.8, 0.5, 0.3, 0.1, 0.0];
+const NOISE: [f32; 5] = [0.9, 0.6, 0.4, 0.2, 0.0];
+
+/// Drops words of `text` as heard with the given readability.
+pub fn degrade<R: Rng>(text: &str, readability: u32, rng: &mut R) -> String {
+    let dropped = DROPPED[(readability.clamp(1, 5) - 1) as usize];
+    text.split(' ')
+        .map(|word| if rng.gen_bool(dropped) { "..." } else { word })
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn noise_wav<R: Rng>(
+    readability: u32,
+    seconds: f32,
+    rng: &mut R,
+) -> std::io::Result<std::path::PathBuf> {
+    let level = NOISE[(readability.clamp(1, 5) - 1) as usize];
+    let samples: Vec<i16> = (0..(SAMPLE_RATE as f32 * seconds) as u32)
+        .map(|_| (rng.gen_range(-level..=level) * i16::MAX as f32 * 0.8) as i16)
+        .collect();
+    write_wav("elp_noise.wav", &samples)
+}
+
+/// Readability given in an answer, as a digit or a spoken number:
+/// "readability 3", "tower, A6KIA, reading you fife".
+pub fn parse_readability(answer: &str) -> Option<u32> {
+    let numbers = [
+        ("1", 1),
+        ("one", 1),
+        ("wun", 1),
+        ("2", 2),
+        ("two", 2),
+        ("too", 2),
+        ("3", 3),
+        ("three", 3),
+        ("tree", 3),
+        ("4", 4),
+        ("four", 4),
+        ("fower", 4),
+        ("5", 5),
+        ("five", 5),
+        ("fife", 5),
+    ];
+    answer
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .rev()
+        .find_map(|word| numbers.iter().find(|(w, _)| *w == word).map(|(_, n)| *n))
+}
+
+/// Speaks `msg` with words dropped and noise matching the readability.
+fn transmit<R: Rng>(
+    msg: &str,
+    readability: u32,
+    generator: &mut Generator<R>,
+) -> Result<(), ElpError> {
+    let config = generator.config().clone();
+    let heard = degrade(msg, readability, generator.rng());
+    let speech = spawn_say(
+        &prepare_message_for_say(&heard, config.region),
+        &config.voice,
+        &config,
+    )?;
+    let noise = if readability < 5 && config.backend != "silent" {
+        let seconds = msg.split(' ').count() as f32 * 0.4;
+        spawn_play(&noise_wav(readability, seconds, generator.rng())?)?
+    } else {
+        None
+    };
+
+    wait(speech)?;
+    wait(noise)?;
+    Ok(())
+}
+
+/// Two questions per round: the meaning of the readability the station
+/// reports, then the readability of a degraded radio check call, one
+/// step off still counts as the scale is a judgement.
+pub fn run_radio_check_session<R: Rng>(
+    generator: &mut Generator<R>,
+) -> Result<(u32, u32), ElpError> {
+    let config = generator.config().clone();
+    let letters = ["a", "b", "c", "d", "e"];
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let station = generator.get_station();
+        let reported = generator.rng().gen_range(1..=5);
+        let reply = format!(
+            "{}, {}, readability {}",
+            config.call_sign, station, reported
+        );
+        transmit(&reply, 5, generator)?;
+
+        let mut options: Vec<&str> = READABILITY.to_vec();
+        options.shuffle(generator.rng());
+        for (letter, meaning) in letters.iter().zip(&options) {
+            println!("  {}) {}", letter, meaning);
+        }
+        let answer = ask(&format!(
+            "[{}/{}] The station reads you: ",
+            n, config.num_msg
+        ))?;
+        let meaning = READABILITY[reported as usize - 1];
+        let chosen = letters
+            .iter()
+            .position(|l| *l == answer)
+            .map(|i| options[i]);
+        let meaning_ok = chosen == Some(meaning) || answer == meaning;
+
+        let readability = generator.rng().gen_range(1..=5);
+        let call = format!(
+            "{}, {}, radio check, how do you read",
+            config.call_sign, station
+        );
+        transmit(&call, readability, generator)?;
+        let answer = ask(&format!("[{}/{}] Your response: ", n, config.num_msg))?;
+        let rated = parse_readability(&answer);
+        let rating_ok = rated.is_some_and(|r| r.abs_diff(readability) <= 1);
+
+        println!("{}", reply);
+        if !meaning_ok {
+            println!("  readability {} means {}", reported, meaning);
+        }
+        if !rating_ok {
+            println!(
+                "  the call was sent with readability {} ({})",
+                readability,
+                READABILITY[readability as usize - 1]
+            );
+        }
+        if meaning_ok && rating_ok {
+            println!("Correct.");
+            score += 1;
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse_readability() {
+        use crate::radio_check::parse_readability;
+
+        assert_eq!(parse_readability("tower, A6KIA, readability 3"), Some(3));
+        assert_eq!(parse_readability("reading you fife"), Some(5));
+        assert_eq!(parse_readability("Readability Two"), Some(2));
+        assert_eq!(parse_readability("say again"), None);
+    }
+
+    #[test]
+    fn test_degrade() {
+        use crate::radio_check::degrade;
+
+        let mut rng = rand::thread_rng();
+        let text = "A6KIA, munich tower, radio check, how do you read";
+        assert_eq!(degrade(text, 5, &mut rng), text);
+        let heard = degrade(text, 1, &mut rng);
+        assert_eq!(heard.split(' ').count(), text.split(' ').count());
+    }
+}
@@ -0,0 +1,421 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::parser::{parse_values, tokens as value_tokens, Value};
+use rand::Rng;
+use std::mem::discriminant;
+
+const ROUTE: &str = "ICAO Doc 4444 4.5.7.5.1 a)";
+const RUNWAY: &str = "ICAO Doc 4444 4.5.7.5.1 b)";
+const ITEMS: &str = "ICAO Doc 4444 4.5.7.5.1 c)";
+const OTHER: &str = "ICAO Doc 4444 4.5.7.5.2";
+const NOT_LISTED: &str = "not listed in ICAO Doc 4444 4.5.7.5.1";
+
+/// Whether an instruction element has to be read back, and why.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub item: &'static str,
+    pub mandatory: bool,
+    pub reference: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const fn rule(
+    item: &'static str,
+    mandatory: bool,
+    reference: &'static str,
+    keywords: &'static [&'static str],
+) -> Rule {
+    Rule {
+        item,
+        mandatory,
+        reference,
+        keywords,
+    }
+}
+
+// searched in order, the first rule with a keyword in the element wins,
+// "a+b" needs both words: "cross" alone is a level restriction
+const RULES: [Rule; 13] = [
+    rule(
+        "traffic information",
+        false,
+        NOT_LISTED,
+        &["traffic", "o'clock"],
+    ),
+    rule(
+        "wind",
+        false,
+        NOT_LISTED,
+        &["wind", "varying", "gusting", "gusts"],
+    ),
+    rule(
+        "runway clearance",
+        true,
+        RUNWAY,
+        &[
+            "land",
+            "take-off",
+            "takeoff",
+            "line",
+            "taxi",
+            "short+runway",
+            "cross+runway",
+            "enter+runway",
+            "backtrack",
+        ],
+    ),
+    rule("frequency", true, ITEMS, &["contact", "monitor"]),
+    rule("approach clearance", true, OTHER, &["cleared+approach"]),
+    rule("route clearance", true, ROUTE, &["cleared", "clearance"]),
+    rule("runway", true, ITEMS, &["runway"]),
+    rule("altimeter setting", true, ITEMS, &["qnh", "altimeter"]),
+    rule("squawk", true, ITEMS, &["squawk", "transponder"]),
+    rule(
+        "level",
+        true,
+        ITEMS,
+        &["level", "altitude", "climb", "descend"],
+    ),
+    rule("heading", true, ITEMS, &["heading"]),
+    rule("speed", true, ITEMS, &["speed"]),
+    rule("other", false, NOT_LISTED, &[]),
+];
+
+/// The read-back rule of an instruction element, by keyword or by the
+/// values it carries; anything else need not be read back.
+pub fn readback_rule(element: &str) -> Rule {
+    let words = value_tokens(element);
+    if let Some(rule) = RULES.iter().find(|rule| {
+        rule.keywords
+            .iter()
+            .any(|k| k.split('+').all(|k| words.iter().any(|w| w == k)))
+    }) {
+        return *rule;
+    }
+    let item = match parse_values(element).first() {
+        Some(Value::Runway(_)) => "runway",
+        Some(Value::Qnh(_)) => "altimeter setting",
+        Some(Value::Squawk(_)) => "squawk",
+        Some(Value::Frequency(_)) => "frequency",
+        Some(Value::FlightLevel(_) | Value::Altitude(_)) => "level",
+        Some(Value::Heading(_) | Value::Turn { .. }) => "heading",
+        Some(Value::Speed(_)) => "speed",
+        Some(Value::Wind { .. }) => "wind",
+        None => "other",
+    };
+    *RULES.iter().find(|rule| rule.item == item).unwrap()
+}
+
+/// An omitted element was not read back at all, an incorrect one was read
+/// back with a wrong value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Problem {
+    Omitted,
+    Incorrect,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub element: String,
+    pub problem: Problem,
+    pub rule: Rule,
+}
+
+fn normalize(token: &str) -> String {
+    let token = token.trim().to_lowercase();
+    if !token.is_empty() && token.chars().all(|c| c.is_ascii_digit()) {
+        let trimmed = token.trim_start_matches('0');
+        if trimmed.is_empty() {
+            "0".to_string()
+        } else {
+            trimmed.to_string()
+        }
+    } else {
+        token
+    }
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.replace([',', '!'], " ")
+        .split_whitespace()
+        .map(normalize)
+        .collect()
+}
+
+/// Words of an instruction that have to appear in the readback:
+/// every value (anything with a digit) plus left/right, or the
+/// meaningful words when the instruction carries no value.
+pub fn key_tokens(element: &str) -> Vec<String> {
+    let words = tokens(element);
+    let values: Vec<String> = words
+        .iter()
+        .filter(|w| {
+            w.chars().any(|c| c.is_ascii_digit())
+                || ["left", "right", "centre", "center"].contains(&w.as_str())
+        })
+        .cloned()
+        .collect();
+
+    if values.is_empty() {
+        words.into_iter().filter(|w| w.len() > 3).collect()
+    } else {
+        values
+    }
+}
+
+/// A heading read back without the direction of the turn is still a
+/// heading, only a wrong one.
+fn same_kind(a: &Value, b: &Value) -> bool {
+    let heading = |v: &Value| matches!(v, Value::Heading(_) | Value::Turn { .. });
+    discriminant(a) == discriminant(b) || (heading(a) && heading(b))
+}
+
+/// How `element` was read back in `answer`, `None` when it was correct.
+fn check_element(element: &str, answer: &str) -> Option<Problem> {
+    // values are compared whatever way they were typed
+    let values = parse_values(element);
+    if !values.is_empty() {
+        let answer_values = parse_values(answer);
+        return if values.iter().all(|v| answer_values.contains(v)) {
+            None
+        } else if values
+            .iter()
+            .any(|v| answer_values.iter().any(|a| same_kind(v, a)))
+        {
+            Some(Problem::Incorrect)
+        } else {
+            Some(Problem::Omitted)
+        };
+    }
+
+    let answer_tokens = tokens(answer);
+    let keys = key_tokens(element);
+    let found = keys.iter().filter(|k| answer_tokens.contains(k)).count();
+    let has_value = keys.iter().any(|k| k.chars().any(|c| c.is_ascii_digit()));
+    let complete = if has_value {
+        found == keys.len()
+    } else {
+        found * 2 >= keys.len()
+    };
+    if complete {
+        None
+    } else if found == 0 {
+        Some(Problem::Omitted)
+    } else {
+        Some(Problem::Incorrect)
+    }
+}
+
+/// The call sign, the first comma separated block of `msg`, and the
+/// instruction elements after it.
+fn split_message(msg: &str) -> (String, Vec<String>) {
+    let mut elements = msg
+        .split(',')
+        .map(|e| e.replace('!', "").trim().to_string())
+        .filter(|e| !e.is_empty());
+    let call_sign = elements.next().unwrap_or_default();
+    (call_sign, elements.collect())
+}
+
+fn has_call_sign(call_sign: &str, answer: &str) -> bool {
+    let compact = |s: &str| s.replace(' ', "").to_lowercase();
+    compact(answer).contains(&compact(call_sign))
+}
+
+/// Returns the instructions of `msg` missing from `answer`, the first
+/// comma separated block of `msg` is the call sign.
+pub fn check_readback(msg: &str, answer: &str) -> Vec<String> {
+    let (call_sign, elements) = split_message(msg);
+    let mut missing = Vec::new();
+    if !has_call_sign(&call_sign, answer) {
+        missing.push(call_sign);
+    }
+    missing.extend(
+        elements
+            .into_iter()
+            .filter(|element| check_element(element, answer).is_some()),
+    );
+    missing
+}
+
+/// Scores a readback of the ATC message `msg` by the read-back rules:
+/// omitted mandatory elements and elements read back with a wrong value,
+/// omitted optional elements such as the wind are fine.
+pub fn review_readback(msg: &str, answer: &str) -> Vec<Finding> {
+    let (call_sign, elements) = split_message(msg);
+    let mut findings = Vec::new();
+    if !has_call_sign(&call_sign, answer) {
+        findings.push(Finding {
+            element: call_sign,
+            problem: Problem::Omitted,
+            rule: rule("call sign", true, "ICAO Annex 10 Vol II 5.2.1.7.3.1", &[]),
+        });
+    }
+
+    for element in elements {
+        let rule = readback_rule(&element);
+        match check_element(&element, answer) {
+            Some(Problem::Omitted) if !rule.mandatory => {}
+            Some(problem) => findings.push(Finding {
+                element,
+                problem,
+                rule,
+            }),
+            None => {}
+        }
+    }
+    findings
+}
+
+pub fn run_readback_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let msg = generator.get_message(&config.msg_type);
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+
+        let answer = ask(&format!("[{}/{}] Readback: ", n, config.num_msg))?;
+        let findings = review_readback(&msg, &answer);
+
+        println!("{}", msg.replace('!', ""));
+        if findings.is_empty() {
+            println!("Correct.");
+            score += 1;
+        }
+        for problem in [Problem::Omitted, Problem::Incorrect] {
+            for finding in findings.iter().filter(|f| f.problem == problem) {
+                println!(
+                    "  {}: {} ({}, {})",
+                    if problem == Problem::Omitted {
+                        "omitted"
+                    } else {
+                        "incorrect"
+                    },
+                    finding.element,
+                    finding.rule.item,
+                    finding.rule.reference
+                );
+            }
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_key_tokens() {
+        use crate::readback::key_tokens;
+
+        assert_eq!(key_tokens("heading 090"), vec!["90"]);
+        assert_eq!(key_tokens("runway 09 left"), vec!["9", "left"]);
+        assert_eq!(key_tokens("contact munich tower 118.725"), vec!["118.725"]);
+        assert_eq!(
+            key_tokens("engine start-up approved"),
+            vec!["engine", "start-up", "approved"]
+        );
+    }
+
+    #[test]
+    fn test_check_readback() {
+        use crate::readback::check_readback;
+
+        let msg = "A6KIA, heading 090, flight level 350, squawk 4521";
+
+        assert!(check_readback(msg, "heading 90, flight level 350, squawk 4521, A6KIA").is_empty());
+        assert_eq!(
+            check_readback(msg, "heading 90, flight level 340, squawk 4521, A6KIA"),
+            vec!["flight level 350"]
+        );
+        assert_eq!(
+            check_readback(msg, "heading 090 flight level 350 squawk 4521"),
+            vec!["A6KIA"]
+        );
+
+        let msg = "A6KIA, climb flight level 350, contact munich tower 118.725, runway 27 left";
+        let answer =
+            "climbing FL tree fife zero, one one eight decimal seven two five, left 27, A6KIA";
+        assert!(check_readback(msg, answer).is_empty());
+        assert_eq!(
+            check_readback(msg, "climbing FL350, 118.725, right 27, A6KIA"),
+            vec!["runway 27 left"]
+        );
+    }
+
+    #[test]
+    fn test_review_readback() {
+        use crate::readback::{readback_rule, review_readback, Problem};
+
+        assert!(readback_rule("squawk 4521").mandatory);
+        assert_eq!(
+            readback_rule("qnh 1013 hectopascals").item,
+            "altimeter setting"
+        );
+        assert_eq!(
+            readback_rule("taxi to holding point runway 27 via A").item,
+            "runway clearance"
+        );
+        assert!(!readback_rule("wind 270 degrees 15 knots").mandatory);
+        assert!(!readback_rule("varying between 240 and 300 degrees").mandatory);
+        assert!(!readback_rule("traffic 2 o'clock 1000 feet below").mandatory);
+        let rule = readback_rule("cross ROBEG at or above flight level 250");
+        assert_eq!(
+            (rule.item, rule.reference),
+            ("level", "ICAO Doc 4444 4.5.7.5.1 c)")
+        );
+        assert_eq!(
+            readback_rule("cross runway 27 left").item,
+            "runway clearance"
+        );
+        let rule = readback_rule("cleared ILS approach runway 27");
+        assert_eq!(
+            (rule.item, rule.reference),
+            ("approach clearance", "ICAO Doc 4444 4.5.7.5.2")
+        );
+        assert_eq!(
+            readback_rule("contact munich approach 119.740").item,
+            "frequency"
+        );
+        assert_eq!(
+            readback_rule("cleared to zurich via D1V departure").item,
+            "route clearance"
+        );
+
+        let msg = "A6KIA, heading 090, wind 270 degrees 15 knots, flight level 350, squawk 4521";
+        let findings = review_readback(msg, "heading 090, flight level 340, A6KIA");
+        let summary: Vec<(&str, Problem, &str)> = findings
+            .iter()
+            .map(|f| (f.element.as_str(), f.problem, f.rule.item))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("flight level 350", Problem::Incorrect, "level"),
+                ("squawk 4521", Problem::Omitted, "squawk")
+            ]
+        );
+        assert!(findings[1].rule.reference.contains("4.5.7.5.1"));
+        assert!(review_readback(
+            "A6KIA, climb altitude 5 thousand 5 hundred feet",
+            "climbing 5500, A6KIA"
+        )
+        .is_empty());
+
+        let findings = review_readback(msg, "heading 090, wind 270 at 25, FL350, squawk 4521");
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].rule.item, "call sign");
+        assert_eq!(
+            (findings[1].problem, findings[1].rule.item),
+            (Problem::Incorrect, "wind")
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+pub const REGIONS: [&str; 3] = ["ICAO", "FAA", "CAP413"];
+
+const FAA_PHRASES: &str = include_str!("../phrases/faa.toml");
+const CAP413_PHRASES: &str = include_str!("../phrases/cap413.toml");
+
+// US controllers keep "niner" but read the other digits plainly
+const FAA_WORDS: [(&str, &str); 8] = [
+    ("1", "one"),
+    ("2", "two"),
+    ("3", "three"),
+    ("4", "four"),
+    ("5", "five"),
+    ("8", "eight"),
+    ("9", "niner"),
+    (".", "point"),
+];
+
+/// Phraseology rules: ICAO Doc 4444, FAA JO 7110.65 or UK CAP 413.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Region {
+    #[default]
+    #[serde(rename = "ICAO")]
+    Icao,
+    #[serde(rename = "FAA")]
+    Faa,
+    #[serde(rename = "CAP413")]
+    Cap413,
+}
+
+impl Region {
+    pub fn parse(name: &str) -> Option<Region> {
+        match name {
+            "ICAO" => Some(Region::Icao),
+            "FAA" => Some(Region::Faa),
+            "CAP413" => Some(Region::Cap413),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Icao => "ICAO",
+            Region::Faa => "FAA",
+            Region::Cap413 => "CAP413",
+        }
+    }
+
+    /// Spoken words replacing the ICAO ones of the phonetic alphabet.
+    pub fn words(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Region::Faa => &FAA_WORDS,
+            Region::Icao | Region::Cap413 => &[],
+        }
+    }
+
+    /// Phrase file replacing the built-in categories it defines.
+    pub fn phrases(self) -> Option<&'static str> {
+        match self {
+            Region::Icao => None,
+            Region::Faa => Some(FAA_PHRASES),
+            Region::Cap413 => Some(CAP413_PHRASES),
+        }
+    }
+
+    /// Default transition altitude in feet, `--transition-altitude` sets the
+    /// one of a particular airport.
+    pub fn transition_altitude(self) -> u32 {
+        match self {
+            Region::Icao => 5000,
+            Region::Faa => 18000,
+            Region::Cap413 => 6000,
+        }
+    }
+
+    /// Reserved transponder codes besides the emergency ones, with their use.
+    /// The first one is the code for VFR flights.
+    pub fn special_codes(self) -> &'static [(u32, &'static str)] {
+        match self {
+            Region::Icao => &[
+                (7000, "VFR conspicuity"),
+                (2000, "no code assigned"),
+                (1000, "Mode S conspicuity"),
+            ],
+            Region::Faa => &[
+                (1200, "VFR"),
+                (1255, "fire fighting"),
+                (1277, "search and rescue"),
+                (7777, "military interceptor"),
+            ],
+            Region::Cap413 => &[
+                (7000, "conspicuity"),
+                (2000, "no code assigned"),
+                (1000, "Mode S conspicuity"),
+                (33, "parachute dropping"),
+                (7001, "military low level"),
+            ],
+        }
+    }
+
+    /// "taxi to holding point runway 27" or the FAA "taxi to runway 27, hold short".
+    pub fn taxi_to(self, runway: &str) -> String {
+        match self {
+            Region::Faa => format!("taxi to {}, hold short", runway),
+            Region::Icao | Region::Cap413 => format!("taxi to holding point {}", runway),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_region_names() {
+        use crate::region::{Region, REGIONS};
+
+        for name in REGIONS {
+            assert_eq!(Region::parse(name).unwrap().name(), name);
+        }
+        assert_eq!(Region::parse("EASA"), None);
+        assert_eq!(Region::default(), Region::Icao);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::region::Region;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Centre,
+    Right,
+}
+
+/// One end of a runway, "27L" is number 27 on the left side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Runway {
+    pub number: u32,
+    pub side: Option<Side>,
+}
+
+impl Runway {
+    /// Designator such as "09", "27L" or "18C".
+    pub fn parse(designator: &str) -> Option<Runway> {
+        let (number, side) = match designator.chars().last()? {
+            'L' => (&designator[..designator.len() - 1], Some(Side::Left)),
+            'C' => (&designator[..designator.len() - 1], Some(Side::Centre)),
+            'R' => (&designator[..designator.len() - 1], Some(Side::Right)),
+            _ => (designator, None),
+        };
+        let number: u32 = number.parse().ok()?;
+        if (1..=36).contains(&number) {
+            Some(Runway { number, side })
+        } else {
+            None
+        }
+    }
+
+    pub fn designator(self) -> String {
+        let side = match self.side {
+            Some(Side::Left) => "L",
+            Some(Side::Centre) => "C",
+            Some(Side::Right) => "R",
+            None => "",
+        };
+        format!("{:02}{}", self.number, side)
+    }
+
+    /// The other end: 27L is the reciprocal of 09R.
+    pub fn reciprocal(self) -> Runway {
+        let side = match self.side {
+            Some(Side::Left) => Some(Side::Right),
+            Some(Side::Right) => Some(Side::Left),
+            side => side,
+        };
+        Runway {
+            number: (self.number + 17) % 36 + 1,
+            side,
+        }
+    }
+
+    pub fn heading(self) -> u32 {
+        self.number * 10
+    }
+
+    /// "runway 27 left", spoken "runway two seven left".
+    pub fn text(self, region: Region) -> String {
+        let side = match (self.side, region) {
+            (Some(Side::Left), _) => " left",
+            (Some(Side::Right), _) => " right",
+            (Some(Side::Centre), Region::Faa) => " center",
+            (Some(Side::Centre), _) => " centre",
+            (None, _) => "",
+        };
+        format!("runway {:02}{}", self.number, side)
+    }
+}
+
+/// An airport and one end of each of its runways.
+#[derive(Debug, PartialEq)]
+pub struct Airport {
+    pub name: &'static str,
+    pub runways: &'static [&'static str],
+}
+
+pub const AIRPORTS: [Airport; 8] = [
+    Airport {
+        name: "schiphol",
+        runways: &["18R", "06", "09", "18C", "04", "18L"],
+    },
+    Airport {
+        name: "dubai",
+        runways: &["12L", "12R"],
+    },
+    Airport {
+        name: "new york",
+        runways: &["04L", "04R", "13L", "13R"],
+    },
+    Airport {
+        name: "detroit",
+        runways: &["03L", "03C", "03R", "04L", "04R", "09L", "09R"],
+    },
+    Airport {
+        name: "abu dhabi",
+        runways: &["13L", "13R"],
+    },
+    Airport {
+        name: "warsaw",
+        runways: &["11", "15"],
+    },
+    Airport {
+        name: "berlin",
+        runways: &["07L", "07R"],
+    },
+    Airport {
+        name: "al bateen",
+        runways: &["13"],
+    },
+];
+
+/// Smallest angle between two directions in degrees.
+pub fn angle(a: u32, b: u32) -> u32 {
+    let diff = (a % 360 + 360 - b % 360) % 360;
+    diff.min(360 - diff)
+}
+
+impl Airport {
+    /// Both ends of every runway.
+    pub fn ends(&self) -> Vec<Runway> {
+        self.runways
+            .iter()
+            .filter_map(|designator| Runway::parse(designator))
+            .flat_map(|runway| [runway, runway.reciprocal()])
+            .collect()
+    }
+
+    /// Ends facing most into a wind from `wind_direction`, parallel runways
+    /// are all in use.
+    pub fn active_runways(&self, wind_direction: u32) -> Vec<Runway> {
+        let ends = self.ends();
+        let best = ends
+            .iter()
+            .map(|runway| angle(wind_direction, runway.heading()))
+            .min()
+            .unwrap_or(0);
+        ends.into_iter()
+            .filter(|runway| angle(wind_direction, runway.heading()) == best)
+            .collect()
+    }
+
+    /// One of the active runways.
+    pub fn active_runway<R: Rng>(&self, wind_direction: u32, rng: &mut R) -> Runway {
+        *self
+            .active_runways(wind_direction)
+            .choose(rng)
+            .expect("airport without runways")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_runway() {
+        use crate::region::Region;
+        use crate::runway::{Runway, Side};
+
+        let runway = Runway::parse("09R").unwrap();
+        assert_eq!(runway.side, Some(Side::Right));
+        assert_eq!(runway.reciprocal().designator(), "27L");
+        assert_eq!(
+            Runway::parse("18C").unwrap().reciprocal().designator(),
+            "36C"
+        );
+        assert_eq!(Runway::parse("36").unwrap().reciprocal().designator(), "18");
+        assert_eq!(Runway::parse("04").unwrap().reciprocal().designator(), "22");
+        assert_eq!(Runway::parse("37"), None);
+        assert_eq!(Runway::parse("L"), None);
+
+        let runway = Runway::parse("27C").unwrap();
+        assert_eq!(runway.text(Region::Icao), "runway 27 centre");
+        assert_eq!(runway.text(Region::Faa), "runway 27 center");
+        assert_eq!(Runway::parse("09").unwrap().text(Region::Icao), "runway 09");
+    }
+
+    #[test]
+    fn test_active_runway() {
+        use crate::runway::{angle, AIRPORTS};
+
+        assert_eq!(angle(350, 10), 20);
+        assert_eq!(angle(90, 270), 180);
+
+        let berlin = &AIRPORTS[6];
+        let active: Vec<String> = berlin
+            .active_runways(240)
+            .iter()
+            .map(|runway| runway.designator())
+            .collect();
+        assert_eq!(active, vec!["25R", "25L"]);
+
+        for airport in AIRPORTS.iter() {
+            for wind in (10..=360).step_by(10) {
+                let runway = airport.active_runway(wind, &mut rand::thread_rng());
+                assert!(angle(wind, runway.heading()) <= 90);
+            }
+        }
+    }
+}
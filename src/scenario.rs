@@ -0,0 +1,140 @@
+use crate::elp_service::{prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use rand::Rng;
+
+pub const SCENARIOS: [&str; 3] = ["departure", "arrival", "full"];
+
+fn departure<R: Rng>(generator: &mut Generator<R>, runway: &str) -> Vec<String> {
+    let call_sign = generator.config().call_sign.clone();
+    vec![
+        format!(
+            "{}, engine start-up approved, {}",
+            call_sign,
+            generator.get_qnh()
+        ),
+        format!(
+            "{}, {}",
+            call_sign,
+            generator.config().region.taxi_to(runway)
+        ),
+        format!(
+            "{}, {}, {} cleared for take-off",
+            call_sign,
+            generator.get_wind(),
+            runway
+        ),
+        format!(
+            "{}, {}, climb {}",
+            call_sign,
+            generator.get_squawk(),
+            generator.get_altitude()
+        ),
+        format!(
+            "{}, {}",
+            call_sign,
+            generator.get_frequency_for("departure")
+        ),
+        format!(
+            "{}, climb {}, {}",
+            call_sign,
+            generator.get_fl(),
+            generator.get_heading()
+        ),
+    ]
+}
+
+fn arrival<R: Rng>(generator: &mut Generator<R>, runway: &str) -> Vec<String> {
+    let call_sign = generator.config().call_sign.clone();
+    vec![
+        format!(
+            "{}, descend {}, {}",
+            call_sign,
+            generator.get_fl(),
+            generator.get_qnh()
+        ),
+        format!(
+            "{}, {}, descend {}",
+            call_sign,
+            generator.get_heading(),
+            generator.get_altitude()
+        ),
+        format!("{}, {}", call_sign, generator.get_frequency_for("tower")),
+        format!(
+            "{}, {}, {} cleared to land",
+            call_sign,
+            generator.get_wind(),
+            runway
+        ),
+        format!(
+            "{}, vacate left, {}",
+            call_sign,
+            generator.get_frequency_for("ground")
+        ),
+    ]
+}
+
+/// Messages of a scenario in flight order, one runway is used for the whole scenario.
+pub fn get_scenario<R: Rng>(name: &str, generator: &mut Generator<R>) -> Vec<String> {
+    generator.new_surface();
+    match name {
+        "departure" => {
+            let runway = generator.get_rw();
+            departure(generator, &runway)
+        }
+        "arrival" => {
+            let runway = generator.get_rw();
+            arrival(generator, &runway)
+        }
+        "full" => {
+            let runway = generator.get_rw();
+            let mut messages = departure(generator, &runway);
+            generator.new_surface();
+            let runway = generator.get_rw();
+            messages.append(&mut arrival(generator, &runway));
+            messages
+        }
+        _ => Vec::new(),
+    }
+}
+
+pub fn run_scenario<R: Rng>(name: &str, generator: &mut Generator<R>) -> Result<(), ElpError> {
+    let mut config = generator.config().clone();
+
+    for msg in get_scenario(name, generator) {
+        crate::elp_service::be_ready(config.pause);
+
+        println!("{}", msg.replace('!', ""));
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_get_scenario() {
+        use crate::elp_service::{ConfigElp, Generator};
+        use crate::scenario::get_scenario;
+
+        let config = ConfigElp {
+            call_sign: "A6KIA".to_string(),
+            ..Default::default()
+        };
+        let mut generator = Generator::new(config, rand::thread_rng());
+
+        let departure = get_scenario("departure", &mut generator);
+        assert_eq!(departure.len(), 6);
+        assert!(departure.iter().all(|msg| msg.starts_with("A6KIA, ")));
+        assert!(departure[2].contains("cleared for take-off"));
+
+        let runway = departure[1].replace("A6KIA, taxi to holding point ", "");
+        assert!(departure[2].contains(&runway));
+
+        assert_eq!(get_scenario("full", &mut generator).len(), 11);
+        assert!(get_scenario("unknown", &mut generator).is_empty());
+    }
+}
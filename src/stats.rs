@@ -0,0 +1,147 @@
+use crate::config_file::data_dir;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionResult {
+    pub timestamp: u64,
+    pub exercise: String,
+    pub correct: u32,
+    pub total: u32,
+}
+
+impl SessionResult {
+    pub fn to_line(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.timestamp, self.exercise, self.correct, self.total
+        )
+    }
+
+    pub fn from_line(line: &str) -> Option<SessionResult> {
+        let values: Vec<&str> = line.trim().split(',').collect();
+        if values.len() != 4 {
+            return None;
+        }
+        Some(SessionResult {
+            timestamp: values[0].parse().ok()?,
+            exercise: values[1].to_string(),
+            correct: values[2].parse().ok()?,
+            total: values[3].parse().ok()?,
+        })
+    }
+}
+
+/// `$XDG_DATA_HOME/elp/stats.csv`, falling back to `~/.local/share/elp/stats.csv`.
+pub fn stats_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("stats.csv"))
+}
+
+pub fn record(exercise: &str, correct: u32, total: u32) -> Result<(), std::io::Error> {
+    let path = match stats_path() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let result = SessionResult {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        exercise: exercise.to_string(),
+        correct,
+        total,
+    };
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", result.to_line())
+}
+
+pub fn load() -> Result<Vec<SessionResult>, std::io::Error> {
+    let path = match stats_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .filter_map(SessionResult::from_line)
+        .collect())
+}
+
+/// Sums up sessions per exercise: (sessions, correct, total).
+pub fn summarize(results: &[SessionResult]) -> BTreeMap<String, (u32, u32, u32)> {
+    let mut summary: BTreeMap<String, (u32, u32, u32)> = BTreeMap::new();
+    for result in results {
+        let entry = summary.entry(result.exercise.clone()).or_default();
+        entry.0 += 1;
+        entry.1 += result.correct;
+        entry.2 += result.total;
+    }
+    summary
+}
+
+pub fn print_stats() -> Result<(), std::io::Error> {
+    let results = load()?;
+    if results.is_empty() {
+        println!("No sessions recorded yet.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:>8} {:>8} {:>8} {:>6}",
+        "exercise", "sessions", "correct", "total", "%"
+    );
+    for (exercise, (sessions, correct, total)) in summarize(&results) {
+        let percent = if total > 0 {
+            correct as f32 * 100.0 / total as f32
+        } else {
+            0.0
+        };
+        println!(
+            "{:<20} {:>8} {:>8} {:>8} {:>6.1}",
+            exercise, sessions, correct, total, percent
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_session_result_line() {
+        use crate::stats::SessionResult;
+
+        let result = SessionResult {
+            timestamp: 1700000000,
+            exercise: "readback".to_string(),
+            correct: 3,
+            total: 5,
+        };
+        assert_eq!(result.to_line(), "1700000000,readback,3,5");
+        assert_eq!(SessionResult::from_line(&result.to_line()), Some(result));
+        assert_eq!(SessionResult::from_line("garbage"), None);
+    }
+
+    #[test]
+    fn test_summarize() {
+        use crate::stats::{summarize, SessionResult};
+
+        let results: Vec<SessionResult> =
+            ["1,quiz-blocked,2,4", "2,quiz-blocked,3,4", "3,readback,1,1"]
+                .iter()
+                .filter_map(|line| SessionResult::from_line(line))
+                .collect();
+        let summary = summarize(&results);
+
+        assert_eq!(summary["quiz-blocked"], (2, 5, 8));
+        assert_eq!(summary["readback"], (1, 1, 1));
+    }
+}
@@ -0,0 +1,330 @@
+use crate::error::ElpError;
+use crate::region::Region;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Slots filled by the built-in generators, any other slot name must be a
+/// phrase category and is filled with one of its templates.
+pub const SLOTS: [(&str, &str); 37] = [
+    ("callsign", "call sign from the configuration"),
+    ("flight_level", "flight level 350"),
+    ("altitude", "altitude 5 thousand 5 hundred feet"),
+    ("heading", "heading 270"),
+    ("squawk", "squawk 4521"),
+    ("runway", "runway 27 left"),
+    ("station", "munich tower, apron or departure"),
+    ("frequency", "132.005 or 118.1"),
+    ("contact", "contact munich tower 118.705"),
+    ("ground_contact", "contact dubai ground 121.725"),
+    (
+        "qnh",
+        "qnh 1013 hectopascals, UK qnh 1013, FAA altimeter 2992",
+    ),
+    ("qnh_hpa", "qnh 1013 hectopascals"),
+    (
+        "wind",
+        "wind 270 degrees 15 knots gusting 25 knots, wind calm, ...",
+    ),
+    ("wind_metar", "27015G25KT 240V300"),
+    (
+        "runway_winds",
+        "wind touchdown 270 degrees 12 knots, midpoint ...",
+    ),
+    ("wind_shear", "wind shear reported on final runway 27, ..."),
+    ("temperature", "temperature 15 dew point 10"),
+    ("letter", "one letter A-Z"),
+    ("airport", "dubai"),
+    ("destination", "munich"),
+    ("sid", "T1B"),
+    ("taxiway", "L F M"),
+    ("transition_level", "60 above transition altitude 5000"),
+    ("visibility", "5000"),
+    ("cloud_few", "1200"),
+    ("cloud_scattered", "3000"),
+    ("cloud_broken", "5500"),
+    ("approach_type", "ILS, RNP (RNAV for FAA) or visual"),
+    ("speed", "180 knots"),
+    ("distance", "4 miles"),
+    (
+        "transponder",
+        "squawk ident, squawk standby, reset squawk 4521",
+    ),
+    ("readability", "1 to 5"),
+    ("level_change", "climb flight level 250, expedite ..."),
+    ("direct", "proceed direct ROBEG"),
+    ("hold", "hold at ROTEL, inbound track 090, ..."),
+    ("traffic", "traffic 2 o'clock, 5 miles, ..."),
+    ("avoiding_action", "avoiding action, turn left ..."),
+];
+
+const BUILTIN: &str = include_str!("../phrases/default.toml");
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Part {
+    Literal(String),
+    Slot(String),
+}
+
+/// A phrase such as `"{callsign}, climb {flight_level}"`, `{{` and `}}`
+/// stand for literal braces.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    pub text: String,
+    pub parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Template, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) if c.is_ascii_alphanumeric() || c == '_' => name.push(c),
+                            Some(c) => {
+                                return Err(format!("invalid character '{}' in slot name", c))
+                            }
+                            None => return Err("unclosed '{'".to_string()),
+                        }
+                    }
+                    if name.is_empty() {
+                        return Err("empty slot '{}'".to_string());
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Slot(name));
+                }
+                '}' => return Err("unmatched '}'".to_string()),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Template {
+            text: text.to_string(),
+            parts,
+        })
+    }
+
+    pub fn slots(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Slot(name) => Some(name.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PhraseFile {
+    phrases: BTreeMap<String, Vec<String>>,
+}
+
+/// Templates grouped by category, a category is also a message type.
+#[derive(Clone, Debug, Default)]
+pub struct PhraseBook {
+    categories: BTreeMap<String, Vec<Template>>,
+}
+
+impl PhraseBook {
+    /// The phrases shipped with the tool, see `phrases/default.toml`.
+    pub fn builtin() -> PhraseBook {
+        PhraseBook::parse(BUILTIN).unwrap()
+    }
+
+    /// The built-in phrases with the categories of `region` replacing the
+    /// ICAO ones.
+    pub fn builtin_for(region: Region) -> PhraseBook {
+        let mut book = PhraseBook::builtin();
+        if let Some(text) = region.phrases() {
+            for (category, templates) in PhraseBook::parse(text).unwrap().categories {
+                book.categories.insert(category, templates);
+            }
+        }
+        book
+    }
+
+    /// Parses a phrase file:
+    ///
+    /// ```toml
+    /// [phrases]
+    /// clearance = ["cleared to {destination} via {sid} departure"]
+    /// taxi = ["{callsign}, taxi to holding point {runway} via {taxiway}"]
+    /// ```
+    pub fn parse(text: &str) -> Result<PhraseBook, String> {
+        let file: PhraseFile = toml::from_str(text).map_err(|e| e.to_string())?;
+        let mut book = PhraseBook::default();
+
+        for (category, phrases) in file.phrases {
+            let mut templates = Vec::new();
+            for phrase in phrases {
+                templates.push(
+                    Template::parse(&phrase)
+                        .map_err(|e| format!("{}: \"{}\": {}", category, phrase, e))?,
+                );
+            }
+            book.categories.insert(category, templates);
+        }
+
+        Ok(book)
+    }
+
+    pub fn load(path: &Path) -> Result<PhraseBook, ElpError> {
+        let data_error = |message: String| ElpError::DataFile {
+            path: path.to_path_buf(),
+            message,
+        };
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| data_error(format!("can't read phrase file: {}", e)))?;
+        let book = PhraseBook::parse(&text).map_err(data_error)?;
+        Ok(book)
+    }
+
+    /// Adds the templates of `other`, categories present in both are appended.
+    pub fn merge(&mut self, other: PhraseBook) {
+        for (category, mut templates) in other.categories {
+            self.categories
+                .entry(category)
+                .or_default()
+                .append(&mut templates);
+        }
+    }
+
+    pub fn categories(&self) -> Vec<&str> {
+        self.categories.keys().map(|k| k.as_str()).collect()
+    }
+
+    pub fn get(&self, category: &str) -> Option<&Vec<Template>> {
+        self.categories.get(category)
+    }
+
+    pub fn is_known_slot(&self, name: &str) -> bool {
+        SLOTS.iter().any(|(slot, _)| *slot == name) || self.categories.contains_key(name)
+    }
+
+    /// True when a phrase of `category` can end up using `category` again.
+    pub fn is_recursive(&self, category: &str) -> bool {
+        let mut visited: Vec<&str> = Vec::new();
+        let mut pending: Vec<&str> = vec![category];
+
+        while let Some(current) = pending.pop() {
+            for template in self.categories.get(current).into_iter().flatten() {
+                for slot in template.slots() {
+                    if SLOTS.iter().any(|(name, _)| *name == slot) {
+                        continue;
+                    }
+                    if slot == category {
+                        return true;
+                    }
+                    if !visited.contains(&slot) {
+                        visited.push(slot);
+                        pending.push(slot);
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns "category: template: slot" for every slot that is neither a
+    /// generator nor a category.
+    pub fn unknown_slots(&self) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for (category, templates) in &self.categories {
+            for template in templates {
+                for slot in template.slots().filter(|slot| !self.is_known_slot(slot)) {
+                    unknown.push(format!("{}: \"{}\": {}", category, template.text, slot));
+                }
+            }
+        }
+        unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse_template() {
+        use crate::template::{Part, Template};
+
+        let template = Template::parse("{callsign}, climb {flight_level} {{now}}").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![
+                Part::Slot("callsign".to_string()),
+                Part::Literal(", climb ".to_string()),
+                Part::Slot("flight_level".to_string()),
+                Part::Literal(" {now}".to_string()),
+            ]
+        );
+        assert_eq!(
+            template.slots().collect::<Vec<&str>>(),
+            vec!["callsign", "flight_level"]
+        );
+
+        assert!(Template::parse("climb {flight_level").is_err());
+        assert!(Template::parse("climb {}").is_err());
+        assert!(Template::parse("climb {flight level}").is_err());
+        assert!(Template::parse("climb }").is_err());
+    }
+
+    #[test]
+    fn test_phrase_book() {
+        use crate::region::Region;
+        use crate::template::PhraseBook;
+
+        let builtin = PhraseBook::builtin();
+        assert!(builtin.unknown_slots().is_empty());
+        assert!(builtin.get("clearance").is_some());
+        assert!(builtin.get("ATIS").is_some());
+
+        let mut book = PhraseBook::parse(
+            r#"
+[phrases]
+clearance = ["cleared {destination} via {sid}"]
+taxi = ["{callsign}, taxi to {stand}"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            book.unknown_slots(),
+            vec!["taxi: \"{callsign}, taxi to {stand}\": stand"]
+        );
+
+        let clearances = builtin.get("clearance").unwrap().len();
+        book.merge(builtin);
+        assert_eq!(book.get("clearance").unwrap().len(), clearances + 1);
+
+        assert!(PhraseBook::parse("[phrases]\ntaxi = [\"{runway\"]").is_err());
+
+        for region in [Region::Faa, Region::Cap413] {
+            let regional = PhraseBook::builtin_for(region);
+            assert!(regional.unknown_slots().is_empty());
+            assert_eq!(regional.categories(), PhraseBook::builtin().categories());
+        }
+        let faa = PhraseBook::builtin_for(Region::Faa);
+        assert!(faa
+            .get("clearance")
+            .unwrap()
+            .iter()
+            .any(|t| t.text.contains("cleared for the option")));
+    }
+}
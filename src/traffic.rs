@@ -0,0 +1,209 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::readback::check_readback;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+const DIRECTIONS: [&str; 5] = [
+    "opposite direction",
+    "same direction",
+    "crossing left to right",
+    "crossing right to left",
+    "converging",
+];
+
+const AIRCRAFT: [&str; 6] = [
+    "!A320",
+    "!B737",
+    "!A380",
+    "light aircraft",
+    "helicopter",
+    "type unknown",
+];
+
+/// Traffic information, `relative` is the level difference in feet,
+/// positive above.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Traffic {
+    pub clock: u32,
+    pub miles: u32,
+    pub relative: i32,
+    pub text: String,
+}
+
+fn relative_level(relative: i32) -> String {
+    match relative {
+        0 => "same level".to_string(),
+        r if r > 0 => format!("!{} feet above", r),
+        r => format!("!{} feet below", -r),
+    }
+}
+
+fn traffic<R: Rng>(generator: &mut Generator<R>, miles: RangeInclusive<u32>) -> Traffic {
+    let rng = generator.rng();
+    let clock = rng.gen_range(1..=12);
+    let miles = rng.gen_range(miles);
+    let relative = [-2000, -1000, -500, 0, 500, 1000, 2000]
+        .choose(rng)
+        .unwrap();
+    let text = format!(
+        "traffic !{} o'clock, {} miles, {}, {}, {}",
+        clock,
+        miles,
+        DIRECTIONS.choose(rng).unwrap(),
+        relative_level(*relative),
+        AIRCRAFT.choose(rng).unwrap()
+    );
+
+    Traffic {
+        clock,
+        miles,
+        relative: *relative,
+        text,
+    }
+}
+
+/// "traffic 2 o'clock, 5 miles, opposite direction, 1000 feet below, A320"
+pub fn get_traffic<R: Rng>(generator: &mut Generator<R>) -> Traffic {
+    traffic(generator, 2..=10)
+}
+
+/// Turn away from the traffic: "avoiding action, turn left immediately
+/// heading 270, traffic 2 o'clock, ...". Returns the traffic and the turn.
+pub fn get_avoiding_action<R: Rng>(generator: &mut Generator<R>) -> (Traffic, String) {
+    let mut traffic = traffic(generator, 1..=3);
+
+    let side = match traffic.clock {
+        1..=5 => "left",
+        7..=11 => "right",
+        _ => ["left", "right"].choose(generator.rng()).unwrap(),
+    };
+    let heading = format!("heading {:03}", generator.rng().gen_range(1..=36) * 10);
+    traffic.text = format!(
+        "avoiding action, turn {} immediately {}, {}",
+        side, heading, traffic.text
+    );
+    (traffic, format!("turn {} {}", side, heading))
+}
+
+/// Clock position and relative level found in a free text answer such as
+/// "2 o'clock 1000 below" or "11 o'clock same level".
+pub fn check_position(traffic: &Traffic, answer: &str) -> bool {
+    let words: Vec<&str> = answer
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let clock = traffic.clock.to_string();
+    let level = traffic.relative.abs().to_string();
+
+    let clock_found = words.contains(&clock.as_str());
+    let level_found = match traffic.relative {
+        0 => words.contains(&"same"),
+        r => {
+            words.contains(&level.as_str())
+                && words.contains(&if r > 0 { "above" } else { "below" })
+        }
+    };
+    clock_found && level_found
+}
+
+/// The trainee states where the traffic is, then gives the pilot response:
+/// "traffic in sight", "negative contact" or the readback of the avoiding
+/// action.
+pub fn run_traffic_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let call_sign = config.call_sign.clone();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let (traffic, response) = if generator.rng().gen_range(0..3) == 0 {
+            let (traffic, turn) = get_avoiding_action(generator);
+            let response = format!("{}, {}", call_sign, turn.replace("turn", "turning"));
+            (traffic, response)
+        } else {
+            let traffic = get_traffic(generator);
+            let response = if generator.rng().gen_bool(0.5) {
+                println!("(you can see the traffic)");
+                format!("{}, traffic in sight", call_sign)
+            } else {
+                println!("(you can't see the traffic)");
+                format!("{}, negative contact", call_sign)
+            };
+            (traffic, response)
+        };
+        let msg = format!("{}, {}", call_sign, traffic.text);
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+
+        let position = ask(&format!(
+            "[{}/{}] Traffic position (e.g. 2 o'clock 1000 below): ",
+            n, config.num_msg
+        ))?;
+        let answer = ask(&format!("[{}/{}] Your response: ", n, config.num_msg))?;
+        let position_ok = check_position(&traffic, &position);
+        let missing = check_readback(&response, &answer);
+
+        println!("{}", msg.replace('!', ""));
+        if !position_ok {
+            println!(
+                "  position: {} o'clock, {}",
+                traffic.clock,
+                relative_level(traffic.relative).replace('!', "")
+            );
+        }
+        for element in &missing {
+            println!("  missing or incorrect: {}", element);
+        }
+        if position_ok && missing.is_empty() {
+            println!("Correct.");
+            score += 1;
+        } else {
+            println!("Expected: {}", response);
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_traffic() {
+        use crate::elp_service::{ConfigElp, Generator};
+        use crate::traffic::{check_position, get_avoiding_action, get_traffic, Traffic};
+
+        let traffic = Traffic {
+            clock: 2,
+            miles: 5,
+            relative: -1000,
+            text: String::new(),
+        };
+        assert!(check_position(&traffic, "2 o'clock, 1000 feet below"));
+        assert!(!check_position(&traffic, "2 o'clock 1000 above"));
+        assert!(!check_position(&traffic, "10 o'clock 1000 below"));
+
+        let mut generator = Generator::new(ConfigElp::default(), rand::thread_rng());
+        for _ in 0..20 {
+            let traffic = get_traffic(&mut generator);
+            assert!(traffic
+                .text
+                .starts_with(&format!("traffic !{} o'clock", traffic.clock)));
+
+            let (traffic, turn) = get_avoiding_action(&mut generator);
+            assert!(traffic.text.starts_with("avoiding action, turn "));
+            assert!(traffic
+                .text
+                .contains(&format!(", {} miles, ", traffic.miles)));
+            if (1..=5).contains(&traffic.clock) {
+                assert!(turn.starts_with("turn left heading "));
+            }
+        }
+    }
+}
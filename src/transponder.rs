@@ -0,0 +1,158 @@
+use crate::elp_service::{ask, Generator};
+use crate::error::ElpError;
+use crate::region::Region;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Codes with the same meaning in every region.
+pub const EMERGENCY_CODES: [(u32, &str); 3] = [
+    (7500, "unlawful interference"),
+    (7600, "radio failure"),
+    (7700, "emergency"),
+];
+
+/// Transponder codes are four octal digits, 0000 to 7777.
+pub fn is_octal(code: u32) -> bool {
+    code <= 7777 && code.to_string().chars().all(|c| c < '8')
+}
+
+/// Use of a reserved code in the region, `None` for an ordinary code.
+pub fn special_code(code: u32, region: Region) -> Option<&'static str> {
+    EMERGENCY_CODES
+        .iter()
+        .chain(region.special_codes())
+        .find(|(c, _)| *c == code)
+        .map(|(_, meaning)| *meaning)
+}
+
+/// Octal code that is neither reserved nor ending in 00, codes of the
+/// form xx00 are not discrete.
+pub fn discrete_code<R: Rng>(region: Region, rng: &mut R) -> u32 {
+    loop {
+        let code: u32 = (0..4).fold(0, |code, _| code * 10 + rng.gen_range(0..8));
+        if !code.is_multiple_of(100) && special_code(code, region).is_none() {
+            return code;
+        }
+    }
+}
+
+/// Code assigned for `context`: "ifr" a discrete code, "conspicuity" the
+/// Mode S conspicuity code 1000 where the region has it, "vfr" the VFR code
+/// of the region. Emergency codes are set by the pilot, see EMERGENCY_CODES.
+pub fn assign_code<R: Rng>(context: &str, region: Region, rng: &mut R) -> Option<u32> {
+    let code = match context {
+        "ifr" => discrete_code(region, rng),
+        "conspicuity" if region.special_codes().iter().any(|(c, _)| *c == 1000) => 1000,
+        "vfr" => region.special_codes()[0].0,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// "squawk 4521", "squawk ident", "squawk standby", "reset squawk 4521", the
+/// VFR code or the Mode S conspicuity code "squawk 1000". The FAA says
+/// "squawk VFR" and "reset transponder, squawk 4521".
+pub fn get_transponder_instruction<R: Rng>(generator: &mut Generator<R>) -> String {
+    let region = generator.config().region;
+    let rng = generator.rng();
+    let code = assign_code("ifr", region, rng).unwrap();
+
+    match (rng.gen_range(0..6), region) {
+        (0, _) => "squawk ident".to_string(),
+        (1, _) => "squawk standby".to_string(),
+        (2, Region::Faa) => format!("reset transponder, squawk {:04}", code),
+        (2, _) => format!("reset squawk {:04}", code),
+        (3, Region::Faa) => "squawk VFR".to_string(),
+        (3, _) => format!("squawk {:04}", assign_code("vfr", region, rng).unwrap()),
+        (4, _) => format!(
+            "squawk {:04}",
+            assign_code("conspicuity", region, rng).unwrap_or(code)
+        ),
+        _ => format!("squawk {:04}", code),
+    }
+}
+
+/// The use of an emergency or reserved code of the region is shown, the
+/// trainee gives the code.
+pub fn run_squawk_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let config = generator.config().clone();
+    let codes: Vec<(u32, &str)> = EMERGENCY_CODES
+        .iter()
+        .chain(config.region.special_codes())
+        .cloned()
+        .collect();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        let (code, meaning) = codes.choose(generator.rng()).unwrap();
+        let answer = ask(&format!(
+            "[{}/{}] Squawk for {} ({}): ",
+            n,
+            config.num_msg,
+            meaning,
+            config.region.name()
+        ))?;
+
+        if answer.parse::<u32>().ok() == Some(*code) && answer.len() == 4 {
+            println!("Correct.");
+            score += 1;
+        } else {
+            println!("Incorrect, squawk {:04}.", code);
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_codes() {
+        use crate::elp_service::{ConfigElp, Generator};
+        use crate::region::{Region, REGIONS};
+        use crate::transponder::{
+            assign_code, discrete_code, get_transponder_instruction, is_octal, special_code,
+        };
+
+        assert!(is_octal(4521));
+        assert!(!is_octal(4581));
+        assert!(!is_octal(10000));
+        assert_eq!(special_code(7600, Region::Faa), Some("radio failure"));
+        assert_eq!(special_code(1200, Region::Faa), Some("VFR"));
+        assert_eq!(special_code(1200, Region::Icao), None);
+
+        let mut rng = rand::thread_rng();
+        for name in REGIONS {
+            let region = Region::parse(name).unwrap();
+            for _ in 0..200 {
+                let code = discrete_code(region, &mut rng);
+                assert!(is_octal(code));
+                assert!(!code.is_multiple_of(100));
+                assert!(special_code(code, region).is_none());
+            }
+        }
+        assert_eq!(assign_code("vfr", Region::Faa, &mut rng), Some(1200));
+        assert_eq!(assign_code("vfr", Region::Cap413, &mut rng), Some(7000));
+        assert_eq!(
+            assign_code("ifr", Region::Faa, &mut rng).map(is_octal),
+            Some(true)
+        );
+        assert_eq!(
+            assign_code("conspicuity", Region::Icao, &mut rng),
+            Some(1000)
+        );
+        assert_eq!(assign_code("conspicuity", Region::Faa, &mut rng), None);
+        for _ in 0..50 {
+            assert_ne!(assign_code("ifr", Region::Icao, &mut rng), Some(1000));
+        }
+        assert_eq!(assign_code("taxi", Region::Faa, &mut rng), None);
+
+        let mut generator = Generator::new(ConfigElp::default(), rand::thread_rng());
+        let instructions: Vec<String> = (0..200)
+            .map(|_| get_transponder_instruction(&mut generator))
+            .collect();
+        assert!(instructions.contains(&"squawk 1000".to_string()));
+        assert!(instructions.contains(&"squawk 7000".to_string()));
+    }
+}
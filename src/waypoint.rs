@@ -0,0 +1,96 @@
+use crate::elp_service::{ask, prepare_message_for_say, say, Generator};
+use crate::error::ElpError;
+use crate::phonetic::spelling;
+use crate::region::Region;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const CONSONANTS: [char; 15] = [
+    'B', 'D', 'F', 'G', 'K', 'L', 'M', 'N', 'P', 'R', 'S', 'T', 'V', 'X', 'Z',
+];
+const VOWELS: [char; 5] = ['A', 'E', 'I', 'O', 'U'];
+
+/// Pronounceable five-letter name, consonant vowel consonant vowel
+/// consonant: "ROBEG", "LAGAV".
+pub fn waypoint_name<R: Rng>(rng: &mut R) -> String {
+    (0..5)
+        .map(|i| {
+            if i % 2 == 0 {
+                *CONSONANTS.choose(rng).unwrap()
+            } else {
+                *VOWELS.choose(rng).unwrap()
+            }
+        })
+        .collect()
+}
+
+/// "proceed direct ROBEG" or "direct to ROBEG", the FAA also says
+/// "cleared direct ROBEG". Returns the instruction and the waypoint.
+pub fn get_direct<R: Rng>(generator: &mut Generator<R>) -> (String, String) {
+    let waypoint = generator.get_waypoint();
+    let forms: &[&str] = match generator.config().region {
+        Region::Faa => &["proceed direct", "cleared direct"],
+        Region::Icao | Region::Cap413 => &["proceed direct", "direct to"],
+    };
+    let form = forms.choose(generator.rng()).unwrap();
+    (format!("{} {}", form, waypoint), waypoint)
+}
+
+/// ATC clears the aircraft direct to a new waypoint and spells it, the
+/// trainee writes the name down.
+pub fn run_waypoint_session<R: Rng>(generator: &mut Generator<R>) -> Result<(u32, u32), ElpError> {
+    let mut config = generator.config().clone();
+    let mut score = 0;
+
+    for n in 1..=config.num_msg {
+        crate::elp_service::be_ready(config.pause);
+
+        let (direct, waypoint) = get_direct(generator);
+        let name = waypoint.replace('!', "");
+        let msg = format!(
+            "{}, {}, I spell, {}",
+            config.call_sign,
+            direct,
+            spelling(&name)
+        );
+        say(
+            prepare_message_for_say(&msg, config.region),
+            false,
+            &mut config,
+        )?;
+
+        let answer = ask(&format!("[{}/{}] Waypoint: ", n, config.num_msg))?;
+        println!("{}", msg.replace('!', ""));
+        if answer.trim().eq_ignore_ascii_case(&name) {
+            println!("Correct.");
+            score += 1;
+        } else {
+            println!("Incorrect, {}.", name);
+        }
+    }
+
+    println!("Score: {}/{}", score, config.num_msg);
+    Ok((score, config.num_msg))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_waypoint_name() {
+        use crate::elp_service::{ConfigElp, Generator};
+        use crate::waypoint::{get_direct, waypoint_name, VOWELS};
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let name = waypoint_name(&mut rng);
+            let pattern: Vec<bool> = name.chars().map(|c| VOWELS.contains(&c)).collect();
+            assert_eq!(pattern, vec![false, true, false, true, false]);
+        }
+
+        let mut generator = Generator::new(ConfigElp::default(), rand::thread_rng());
+        let (direct, waypoint) = get_direct(&mut generator);
+        assert!(direct.ends_with(&waypoint));
+        assert!(direct.starts_with("proceed direct !") || direct.starts_with("direct to !"));
+        assert!(!generator.get_message("direct").contains('{'));
+    }
+}
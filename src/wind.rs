@@ -0,0 +1,236 @@
+use crate::elp_service::Generator;
+use crate::region::Region;
+use rand::Rng;
+
+/// Surface wind, `direction` is `None` for a calm or variable wind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wind {
+    pub direction: Option<u32>,
+    pub speed: u32,
+    pub gust: Option<u32>,
+    /// Extremes of a direction varying by 60 degrees or more.
+    pub varying: Option<(u32, u32)>,
+}
+
+/// Direction in tens of degrees, 360 for north.
+fn direction(degrees: i32) -> u32 {
+    let degrees = degrees.rem_euclid(360) as u32 / 10 * 10;
+    if degrees == 0 {
+        360
+    } else {
+        degrees
+    }
+}
+
+impl Wind {
+    pub fn calm() -> Wind {
+        Wind {
+            direction: None,
+            speed: 0,
+            gust: None,
+            varying: None,
+        }
+    }
+
+    /// Steady wind from `direction` at `speed` knots.
+    pub fn new(direction: u32, speed: u32) -> Wind {
+        Wind {
+            direction: Some(direction),
+            speed,
+            gust: None,
+            varying: None,
+        }
+    }
+
+    /// Mostly steady winds, some calm, variable, gusting or varying ones.
+    pub fn random<R: Rng>(rng: &mut R) -> Wind {
+        match rng.gen_range(0..20) {
+            0 | 1 => Wind::calm(),
+            2 | 3 => Wind {
+                speed: rng.gen_range(1..=5),
+                ..Wind::calm()
+            },
+            kind => {
+                let mut wind = Wind::new(direction(rng.gen_range(10..=360)), rng.gen_range(3..=40));
+                if kind < 8 && wind.speed >= 10 {
+                    wind.gust = Some(wind.speed + rng.gen_range(10..=20));
+                }
+                if kind >= 16 {
+                    let spread = rng.gen_range(3..=5) * 10;
+                    let center = wind.direction.unwrap() as i32;
+                    wind.varying = Some((direction(center - spread), direction(center + spread)));
+                }
+                wind
+            }
+        }
+    }
+
+    pub fn is_calm(&self) -> bool {
+        self.speed == 0
+    }
+
+    /// METAR group: "27015G25KT 240V300", "VRB03KT" or "00000KT".
+    pub fn metar(&self) -> String {
+        let direction = match self.direction {
+            _ if self.is_calm() => "000".to_string(),
+            Some(direction) => format!("{:03}", direction),
+            None => "VRB".to_string(),
+        };
+        let gust = match self.gust {
+            Some(gust) => format!("G{:02}", gust),
+            None => "".to_string(),
+        };
+        let varying = match self.varying {
+            Some((from, to)) => format!(" {:03}V{:03}", from, to),
+            None => "".to_string(),
+        };
+        format!("{}{:02}{}KT{}", direction, self.speed, gust, varying)
+    }
+
+    /// Spoken wind without the leading "wind": "270 degrees 15 knots
+    /// gusting 25 knots" or the FAA "270 at 15 gusts 25".
+    fn spoken(&self, region: Region) -> String {
+        match (self.direction, region) {
+            _ if self.is_calm() => "calm".to_string(),
+            (None, Region::Faa) => format!("variable at {}", self.speed),
+            (None, _) => format!("variable {} knots", self.speed),
+            (Some(direction), Region::Faa) => {
+                let mut text = format!("{:03} at {}", direction, self.speed);
+                if let Some(gust) = self.gust {
+                    text.push_str(&format!(" gusts {}", gust));
+                }
+                if let Some((from, to)) = self.varying {
+                    text.push_str(&format!(
+                        ", wind variable between {:03} and {:03}",
+                        from, to
+                    ));
+                }
+                text
+            }
+            (Some(direction), _) => {
+                let mut text = format!("{:03} degrees {} knots", direction, self.speed);
+                if let Some(gust) = self.gust {
+                    text.push_str(&format!(" gusting {} knots", gust));
+                }
+                if let Some((from, to)) = self.varying {
+                    text.push_str(&format!(
+                        ", varying between {:03} and {:03} degrees",
+                        from, to
+                    ));
+                }
+                text
+            }
+        }
+    }
+
+    /// "wind 270 degrees 15 knots", "wind calm", "wind variable 3 knots".
+    pub fn text(&self, region: Region) -> String {
+        format!("wind {}", self.spoken(region))
+    }
+}
+
+/// Winds at the touchdown zone, midpoint and stop end of the runway in use:
+/// "wind touchdown 270 degrees 12 knots, midpoint ..., stop end ...".
+/// The FAA reports touchdown, midfield and rollout.
+pub fn get_runway_winds<R: Rng>(generator: &mut Generator<R>) -> String {
+    let region = generator.config().region;
+    let wind = generator.surface_wind();
+    let base = wind.direction.unwrap_or(generator.get_runway().heading()) as i32;
+    let names = match region {
+        Region::Faa => ["touchdown", "midfield", "rollout"],
+        Region::Icao | Region::Cap413 => ["touchdown", "midpoint", "stop end"],
+    };
+
+    let winds: Vec<String> = names
+        .iter()
+        .map(|name| {
+            let rng = generator.rng();
+            let point = Wind::new(
+                direction(base + rng.gen_range(-2..=2) * 10),
+                (wind.speed as i32 + rng.gen_range(-4..=4)).max(2) as u32,
+            );
+            format!("{} {}", name, point.spoken(region))
+        })
+        .collect();
+    format!("wind {}", winds.join(", "))
+}
+
+/// "wind shear reported on final runway 27, loss of 15 knots at 500 feet",
+/// the FAA "wind shear alert, runway 27 arrival, 15 knot loss, 2 mile final".
+pub fn get_wind_shear<R: Rng>(generator: &mut Generator<R>) -> String {
+    let runway = generator.get_rw();
+    let region = generator.config().region;
+    let rng = generator.rng();
+    let knots = rng.gen_range(2..=6) * 5;
+    let change = if rng.gen_range(0..3) == 0 {
+        "gain"
+    } else {
+        "loss"
+    };
+
+    match region {
+        Region::Faa => format!(
+            "wind shear alert, {} arrival, {} knot {}, {} mile final",
+            runway,
+            knots,
+            change,
+            rng.gen_range(1..=3)
+        ),
+        Region::Icao | Region::Cap413 => format!(
+            "wind shear reported on final {}, {} of {} knots at {} feet",
+            runway,
+            change,
+            knots,
+            rng.gen_range(2..=10) * 100
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_wind_forms() {
+        use crate::region::Region;
+        use crate::wind::Wind;
+
+        let wind = Wind {
+            gust: Some(25),
+            varying: Some((240, 300)),
+            ..Wind::new(270, 15)
+        };
+        assert_eq!(wind.metar(), "27015G25KT 240V300");
+        assert_eq!(
+            wind.text(Region::Icao),
+            "wind 270 degrees 15 knots gusting 25 knots, varying between 240 and 300 degrees"
+        );
+        assert_eq!(
+            wind.text(Region::Faa),
+            "wind 270 at 15 gusts 25, wind variable between 240 and 300"
+        );
+
+        assert_eq!(Wind::calm().metar(), "00000KT");
+        assert_eq!(Wind::calm().text(Region::Faa), "wind calm");
+        let light = Wind {
+            speed: 3,
+            ..Wind::calm()
+        };
+        assert_eq!(light.metar(), "VRB03KT");
+        assert_eq!(light.text(Region::Icao), "wind variable 3 knots");
+        assert_eq!(Wind::new(50, 8).metar(), "05008KT");
+    }
+
+    #[test]
+    fn test_random_wind() {
+        use crate::wind::Wind;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let wind = Wind::random(&mut rng);
+            assert!(wind.speed <= 50);
+            assert!(wind.gust.is_none_or(|gust| gust >= wind.speed + 10));
+            if let Some(direction) = wind.direction {
+                assert!((10..=360).contains(&direction) && direction % 10 == 0);
+            }
+        }
+    }
+}